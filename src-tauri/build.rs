@@ -1,3 +1,6 @@
 fn main() {
-    tauri_build::build()
+    // The privileged helper is built without the app, and has no Tauri context
+    if std::env::var_os("CARGO_FEATURE_APP").is_some() {
+        tauri_build::build()
+    }
 }
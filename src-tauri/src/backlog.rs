@@ -0,0 +1,125 @@
+use crate::types::{RawSocketEntry, TcpState};
+use std::collections::{HashMap, HashSet};
+
+/// Consecutive scans a listener's accept queue must stay non-empty to be flagged
+pub const STALL_THRESHOLD: u32 = 2;
+
+/// Tracks the accept queues of listening sockets across scans
+///
+/// A listener that isn't calling accept() (a hung dev server, a blocked event
+/// loop) keeps connections queued from one scan to the next, while a healthy
+/// one drains them in between.
+#[derive(Debug, Default)]
+pub struct BacklogTracker {
+    /// Consecutive scans each listener (by inode) was seen with a non-empty queue
+    streaks: HashMap<u64, u32>,
+}
+
+impl BacklogTracker {
+    /// Create a tracker with no history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a scan and return the inodes of listeners with a stalled backlog
+    ///
+    /// Set `complete` when the scan covered every listener (not a port range), so
+    /// history for sockets that have since closed can be dropped.
+    pub fn observe(&mut self, entries: &[RawSocketEntry], complete: bool) -> HashSet<u64> {
+        let listeners: Vec<&RawSocketEntry> = entries
+            .iter()
+            .filter(|e| e.state == Some(TcpState::Listen))
+            .collect();
+
+        if complete {
+            let seen: HashSet<u64> = listeners.iter().map(|e| e.inode).collect();
+            self.streaks.retain(|inode, _| seen.contains(inode));
+        }
+
+        let mut stalled = HashSet::new();
+
+        for entry in listeners {
+            if entry.rx_queue == 0 {
+                self.streaks.remove(&entry.inode);
+                continue;
+            }
+
+            let streak = self.streaks.entry(entry.inode).or_insert(0);
+            *streak += 1;
+            if *streak >= STALL_THRESHOLD {
+                stalled.insert(entry.inode);
+            }
+        }
+
+        stalled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AddressFamily, Protocol};
+
+    fn listener(inode: u64, rx_queue: u32) -> RawSocketEntry {
+        RawSocketEntry {
+            local_address: "127.0.0.1".to_string(),
+            local_port: 3000,
+            remote_address: "0.0.0.0".to_string(),
+            remote_port: 0,
+            inode,
+            protocol: Protocol::Tcp,
+            family: AddressFamily::Ipv4,
+            state: Some(TcpState::Listen),
+            tx_queue: 0,
+            rx_queue,
+            uid: 1000,
+            netns: None,
+        }
+    }
+
+    #[test]
+    fn test_flags_queue_that_stays_full() {
+        let mut tracker = BacklogTracker::new();
+
+        assert!(tracker.observe(&[listener(1, 5)], true).is_empty());
+        assert!(tracker.observe(&[listener(1, 3)], true).contains(&1));
+        assert!(tracker.observe(&[listener(1, 7)], true).contains(&1));
+    }
+
+    #[test]
+    fn test_drained_queue_resets_streak() {
+        let mut tracker = BacklogTracker::new();
+
+        tracker.observe(&[listener(1, 5)], true);
+        assert!(tracker.observe(&[listener(1, 0)], true).is_empty());
+        assert!(tracker.observe(&[listener(1, 5)], true).is_empty());
+    }
+
+    #[test]
+    fn test_closed_listener_forgotten_on_complete_scan() {
+        let mut tracker = BacklogTracker::new();
+
+        tracker.observe(&[listener(1, 5)], true);
+        tracker.observe(&[listener(2, 0)], true);
+        assert!(tracker.observe(&[listener(1, 5)], true).is_empty());
+    }
+
+    #[test]
+    fn test_partial_scan_keeps_other_history() {
+        let mut tracker = BacklogTracker::new();
+
+        tracker.observe(&[listener(1, 5)], true);
+        tracker.observe(&[listener(2, 0)], false);
+        assert!(tracker.observe(&[listener(1, 5)], true).contains(&1));
+    }
+
+    #[test]
+    fn test_ignores_non_listeners() {
+        let mut tracker = BacklogTracker::new();
+        let mut established = listener(1, 5);
+        established.state = Some(TcpState::Established);
+
+        tracker.observe(std::slice::from_ref(&established), true);
+        assert!(tracker.observe(&[established], true).is_empty());
+    }
+}
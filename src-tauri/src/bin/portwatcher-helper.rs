@@ -0,0 +1,24 @@
+//! Privileged helper run through pkexec to stop or signal other users' processes
+//!
+//! Accepts only the requests of `HelperRequest`, each naming processes by PID and
+//! start time, and prints one JSON `ProcessKillResult` per process on stdout.
+
+use portwatcher_lib::helper::{HelperRequest, EXIT_USAGE};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let request = match HelperRequest::parse(&args) {
+        Ok(request) => request,
+        Err(e) => {
+            eprintln!("portwatcher-helper: {}", e);
+            std::process::exit(EXIT_USAGE);
+        }
+    };
+
+    let results = request.run();
+    println!(
+        "{}",
+        serde_json::to_string(&results).expect("kill results always serialize")
+    );
+}
@@ -1,86 +1,543 @@
+use crate::backlog::BacklogTracker;
+use crate::container;
+use crate::kill_log::KillLog;
 use crate::port_scanner::PortScanner;
 use crate::presets::get_builtin_presets;
 use crate::process_manager::ProcessManager;
 use crate::process_resolver::ProcessResolver;
-use crate::types::{KillResult, PortInfo, PortPreset, Protocol, TrayStats};
-use std::collections::HashSet;
+use crate::procfs::ProcFs;
+use crate::protection::ProtectionPolicy;
+use crate::types::{
+    FreePortResult, KillHistoryEntry, KillResult, KillTarget, PortInfo, PortPreset, ProcessDetails,
+    ProcessIdentity, ProcessInfo, ProcessKillResult, ProcessSignal, Protocol, RawSocketEntry,
+    RelaunchError, RelaunchResult, ScanMode, SocketOwner, TrayStats, UnixSocketEntry,
+    UnixSocketInfo,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
-/// Get all listening ports with their process information
+/// How long `relaunch` waits by default for the process to take its port back
+const RELAUNCH_PORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often `relaunch` checks the port
+const RELAUNCH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Accept queue history shared by every scan (window refreshes and the tray thread)
+static BACKLOG: OnceLock<Mutex<BacklogTracker>> = OnceLock::new();
+
+/// Process resolver kept across scans, so only new or changed processes are re-read
+static RESOLVER: OnceLock<Mutex<ProcessResolver>> = OnceLock::new();
+
+/// Get all ports with their process information
+///
+/// Defaults to listening sockets only; pass `ScanMode::All` to include live connections.
 #[tauri::command]
-pub fn get_ports() -> Result<Vec<PortInfo>, String> {
-    // Scan for all listening sockets
-    let raw_entries = PortScanner::scan_all().map_err(|e| e.to_string())?;
+pub fn get_ports(mode: Option<ScanMode>) -> Result<Vec<PortInfo>, String> {
+    // Scan for all sockets matching the mode
+    let raw_entries = scanner()
+        .scan_all(mode.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+
+    let stalled = observe_backlog(&raw_entries, true);
+    Ok(resolve_entries(raw_entries, &stalled))
+}
 
-    // Build process resolver (scans /proc for inode mappings)
-    let resolver = ProcessResolver::new();
+/// Kill a process by PID
+///
+/// Pass the `start_time` from the listed `PortInfo.identity` to refuse killing a
+/// process that reused the PID since. `grace_ms` is how long it gets to exit after
+/// SIGTERM before SIGKILL (3s by default). `port` is what the kill history shows
+/// the process was killed for, in `kill_log` if there is one.
+pub fn kill_process(
+    kill_log: Option<&KillLog>,
+    pid: u32,
+    start_time: Option<u64>,
+    grace_ms: Option<u64>,
+    port: Option<u16>,
+) -> KillResult {
+    process_manager(kill_log, grace_ms).kill_target(KillTarget {
+        pid,
+        start_time,
+        port,
+    })
+}
 
-    // Combine socket entries with process information
-    let mut ports: Vec<PortInfo> = raw_entries
+/// Kill a process by PID using elevated privileges (pkexec)
+pub fn kill_process_elevated(
+    kill_log: Option<&KillLog>,
+    pid: u32,
+    start_time: Option<u64>,
+    grace_ms: Option<u64>,
+    port: Option<u16>,
+) -> KillResult {
+    process_manager(kill_log, grace_ms).kill_target_elevated(KillTarget {
+        pid,
+        start_time,
+        port,
+    })
+}
+
+/// Kill several processes at once, returning a result for each
+///
+/// They are signalled in the order given and share one grace period.
+pub fn kill_processes(
+    kill_log: Option<&KillLog>,
+    targets: Vec<KillTarget>,
+    grace_ms: Option<u64>,
+) -> Vec<ProcessKillResult> {
+    process_manager(kill_log, grace_ms).kill_all(&targets)
+}
+
+/// Kill several processes using elevated privileges, with one pkexec prompt for all
+pub fn kill_processes_elevated(
+    kill_log: Option<&KillLog>,
+    targets: Vec<KillTarget>,
+    grace_ms: Option<u64>,
+) -> Vec<ProcessKillResult> {
+    process_manager(kill_log, grace_ms).kill_all_elevated(&targets)
+}
+
+/// Send a signal (SIGINT, SIGHUP, SIGSTOP, SIGCONT...) to a process
+pub fn send_signal(
+    kill_log: Option<&KillLog>,
+    pid: u32,
+    signal: ProcessSignal,
+    start_time: Option<u64>,
+) -> KillResult {
+    process_manager(kill_log, None).send_signal(pid, signal, start_time)
+}
+
+/// Send a signal to a process using elevated privileges (pkexec)
+pub fn send_signal_elevated(
+    kill_log: Option<&KillLog>,
+    pid: u32,
+    signal: ProcessSignal,
+    start_time: Option<u64>,
+) -> KillResult {
+    process_manager(kill_log, None).send_signal_elevated(pid, signal, start_time)
+}
+
+/// Stop every process holding a port, then re-scan to check it was released
+///
+/// Considers listening and bound sockets of `protocol`, or of every protocol with
+/// ports when omitted. Each process is killed as in `kill_process`; pass
+/// `escalate: false` to never go beyond SIGTERM.
+pub fn free_port(
+    kill_log: Option<&KillLog>,
+    port: u16,
+    protocol: Option<Protocol>,
+    grace_ms: Option<u64>,
+    escalate: Option<bool>,
+) -> Result<FreePortResult, String> {
+    let holders = resolve_entries(port_sockets(port, protocol)?, &HashSet::new());
+
+    // Every owner of every socket, masters first so they can't respawn workers
+    let mut seen = HashSet::new();
+    let targets: Vec<KillTarget> = holders
+        .iter()
+        .flat_map(|info| &info.owners)
+        .filter(|owner| seen.insert(owner.pid))
+        .map(|owner| KillTarget {
+            pid: owner.pid,
+            start_time: owner.start_time,
+            port: Some(port),
+        })
+        .collect();
+
+    let stopped = process_manager(kill_log, grace_ms)
+        .with_escalation(escalate.unwrap_or(true))
+        .kill_all(&targets);
+
+    let remaining = resolve_entries(port_sockets(port, protocol)?, &HashSet::new());
+
+    Ok(FreePortResult {
+        port,
+        protocol,
+        stopped,
+        port_free: remaining.is_empty(),
+        remaining,
+    })
+}
+
+/// Kill a process and all of its descendants
+pub fn kill_process_tree(
+    kill_log: Option<&KillLog>,
+    pid: u32,
+    grace_ms: Option<u64>,
+) -> Vec<ProcessKillResult> {
+    process_manager(kill_log, grace_ms).kill_tree(pid)
+}
+
+/// Kill every process in a process's group
+pub fn kill_process_group(
+    kill_log: Option<&KillLog>,
+    pid: u32,
+    grace_ms: Option<u64>,
+) -> Vec<ProcessKillResult> {
+    process_manager(kill_log, grace_ms).kill_group(pid)
+}
+
+/// Scan a specific port range
+#[tauri::command]
+pub fn scan_port_range(
+    start: u16,
+    end: u16,
+    mode: Option<ScanMode>,
+) -> Result<Vec<PortInfo>, String> {
+    // Scan for sockets in the specified range
+    let raw_entries = scanner()
+        .scan_range(start, end, mode.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+
+    let stalled = observe_backlog(&raw_entries, false);
+    Ok(resolve_entries(raw_entries, &stalled))
+}
+
+/// Get the TCP connections on a local port, in every state but LISTEN
+///
+/// Answers "who is connected to my port 5432 right now" for a listening port,
+/// including connections still opening or closing (SYN_RECV, CLOSE_WAIT, TIME_WAIT).
+#[tauri::command]
+pub fn get_port_connections(port: u16) -> Result<Vec<PortInfo>, String> {
+    let connections = scanner()
+        .scan_connections(port)
+        .map_err(|e| e.to_string())?;
+
+    Ok(resolve_entries(connections, &HashSet::new()))
+}
+
+/// Get Unix domain sockets with their process information
+///
+/// Defaults to listening sockets only; pass `ScanMode::All` to include connected ones.
+#[tauri::command]
+pub fn get_unix_sockets(mode: Option<ScanMode>) -> Result<Vec<UnixSocketInfo>, String> {
+    let raw_entries = scanner()
+        .scan_unix(mode.unwrap_or_default())
+        .map_err(|e| e.to_string())?;
+
+    Ok(resolve_unix_entries(raw_entries))
+}
+
+/// Combine Unix socket entries with process information, named sockets first
+///
+/// Sockets whose process can't be inspected are kept with no process, attributed
+/// to the owner of the socket file when they are bound to a path.
+fn resolve_unix_entries(raw_entries: Vec<UnixSocketEntry>) -> Vec<UnixSocketInfo> {
+    let inodes: HashSet<u64> = raw_entries.iter().map(|e| e.inode).collect();
+    let resolver = refreshed_resolver(&inodes);
+
+    let mut sockets: Vec<UnixSocketInfo> = raw_entries
         .into_iter()
-        .filter_map(|entry| {
+        .map(|entry| {
             // Try to resolve the process for this socket
-            let process_info = resolver.resolve(entry.inode)?;
+            let (pid, process_name, user) = match resolver.resolve(entry.inode) {
+                Some(info) => (Some(info.pid), Some(info.name), Some(info.user)),
+                None => (None, None, entry.path.as_deref().and_then(socket_file_user)),
+            };
 
-            Some(PortInfo {
-                pid: process_info.pid,
-                process_name: process_info.name,
-                port: entry.local_port,
-                protocol: entry.protocol,
-                address: entry.local_address,
-                user: process_info.user,
-            })
+            UnixSocketInfo {
+                pid,
+                process_name,
+                path: entry.path,
+                abstract_name: entry.abstract_name,
+                socket_type: entry.socket_type,
+                state: entry.state,
+                listening: entry.listening,
+                inode: entry.inode,
+                user,
+            }
         })
         .collect();
 
-    // Sort by port number for consistent display
-    ports.sort_by_key(|p| p.port);
+    // Sort by name for consistent display, named sockets first
+    sockets.sort_by(|a, b| {
+        let a_name = a.path.as_ref().or(a.abstract_name.as_ref());
+        let b_name = b.path.as_ref().or(b.abstract_name.as_ref());
+        (a_name.is_none(), a_name).cmp(&(b_name.is_none(), b_name))
+    });
 
-    Ok(ports)
+    sockets
 }
 
-/// Kill a process by PID
-#[tauri::command]
-pub fn kill_process(pid: u32) -> KillResult {
-    ProcessManager::kill(pid)
+/// Username of the owner of the socket file at `path`
+///
+/// The path is looked up in our own mount namespace, so a containerized
+/// process's socket only resolves if the same path exists on the host.
+fn socket_file_user(path: &str) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.file_type().is_socket() {
+        return None;
+    }
+    Some(ProcessResolver::get_username(metadata.uid()))
 }
 
-/// Kill a process by PID using elevated privileges (pkexec)
+/// Get detailed information about a process (command line, resources, terminal...)
 #[tauri::command]
-pub fn kill_process_elevated(pid: u32) -> KillResult {
-    ProcessManager::kill_elevated(pid)
+pub fn get_process_details(pid: u32) -> Result<ProcessDetails, String> {
+    shared_resolver()
+        .get_process_details(pid)
+        .ok_or_else(|| format!("Process {} not found", pid))
 }
 
-/// Scan a specific port range
+/// Get a process and its ancestors, nearest first (e.g. node, npm, bash, systemd)
 #[tauri::command]
-pub fn scan_port_range(start: u16, end: u16) -> Result<Vec<PortInfo>, String> {
-    // Scan for sockets in the specified range
-    let raw_entries = PortScanner::scan_range(start, end).map_err(|e| e.to_string())?;
+pub fn get_process_ancestry(pid: u32) -> Result<Vec<ProcessInfo>, String> {
+    let chain = shared_resolver().get_ancestry(pid);
+    if chain.is_empty() {
+        return Err(format!("Process {} not found", pid));
+    }
+    Ok(chain)
+}
 
-    // Build process resolver (scans /proc for inode mappings)
-    let resolver = ProcessResolver::new();
+/// Lock the shared resolver
+fn shared_resolver() -> MutexGuard<'static, ProcessResolver> {
+    let resolver =
+        RESOLVER.get_or_init(|| Mutex::new(ProcessResolver::with_procfs(ProcFs::from_env())));
+    // A poisoned lock only means an earlier scan panicked; the cache is still usable
+    resolver.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Lock the shared resolver, updated for the given socket inodes
+fn refreshed_resolver(inodes: &HashSet<u64>) -> MutexGuard<'static, ProcessResolver> {
+    let mut resolver = shared_resolver();
+    resolver.refresh(inodes);
+    resolver
+}
+
+/// Record a scan in the shared backlog tracker, returning the stalled listeners' inodes
+fn observe_backlog(entries: &[RawSocketEntry], complete: bool) -> HashSet<u64> {
+    let tracker = BACKLOG.get_or_init(|| Mutex::new(BacklogTracker::new()));
+    // A poisoned lock only means an earlier scan panicked; the history is still usable
+    let mut tracker = tracker.lock().unwrap_or_else(|e| e.into_inner());
+    tracker.observe(entries, complete)
+}
+
+/// Combine socket entries with process information, sorted by port number
+///
+/// Sockets whose process can't be inspected (another user's, without root) are
+/// kept with no process and attributed to the socket's UID instead.
+/// Listeners whose inode is in `stalled` are flagged as having a stuck accept queue.
+fn resolve_entries(raw_entries: Vec<RawSocketEntry>, stalled: &HashSet<u64>) -> Vec<PortInfo> {
+    let inodes: HashSet<u64> = raw_entries.iter().map(|e| e.inode).collect();
+    let resolver = refreshed_resolver(&inodes);
+    let mut usernames: HashMap<u32, String> = HashMap::new();
 
-    // Combine socket entries with process information
     let mut ports: Vec<PortInfo> = raw_entries
         .into_iter()
-        .filter_map(|entry| {
-            // Try to resolve the process for this socket
-            let process_info = resolver.resolve(entry.inode)?;
+        .map(|entry| {
+            // Try to resolve the processes holding this socket, master first
+            let holders = resolver.resolve_all(entry.inode);
+            let owners: Vec<SocketOwner> = holders
+                .iter()
+                .enumerate()
+                .map(|(i, info)| SocketOwner {
+                    pid: info.pid,
+                    ppid: info.ppid,
+                    process_name: info.name.clone(),
+                    user: info.user.clone(),
+                    start_time: info.start_time,
+                    master: i == 0,
+                })
+                .collect();
+
+            let master = holders.into_iter().next();
+            let identity = master.as_ref().and_then(|info| {
+                Some(ProcessIdentity {
+                    pid: info.pid,
+                    start_time: info.start_time?,
+                })
+            });
+
+            let (pid, process_name, container, user) = match master {
+                Some(info) => (Some(info.pid), Some(info.name), info.container, info.user),
+                None => {
+                    let user = usernames
+                        .entry(entry.uid)
+                        .or_insert_with(|| ProcessResolver::get_username(entry.uid))
+                        .clone();
+                    (None, None, None, user)
+                }
+            };
+
+            // Unconnected sockets report a wildcard peer with port 0
+            let (remote_address, remote_port) = if entry.remote_port != 0 {
+                (Some(entry.remote_address), Some(entry.remote_port))
+            } else {
+                (None, None)
+            };
 
-            Some(PortInfo {
-                pid: process_info.pid,
-                process_name: process_info.name,
+            PortInfo {
+                pid,
+                process_name,
+                identity,
                 port: entry.local_port,
                 protocol: entry.protocol,
                 address: entry.local_address,
-                user: process_info.user,
-            })
+                family: entry.family,
+                state: entry.state,
+                remote_address,
+                remote_port,
+                tx_queue: entry.tx_queue,
+                rx_queue: entry.rx_queue,
+                backlog_stalled: stalled.contains(&entry.inode),
+                netns: entry.netns,
+                container,
+                owners,
+                uid: entry.uid,
+                user,
+            }
         })
         .collect();
+    drop(resolver);
+
+    name_containers(&mut ports);
 
     // Sort by port number for consistent display
     ports.sort_by_key(|p| p.port);
 
-    Ok(ports)
+    ports
+}
+
+/// Fill in container names the resolver has not cached yet
+///
+/// Runtime sockets are queried here, after the resolver is released, so a slow
+/// runtime never holds up other scans. Failed lookups are not cached.
+fn name_containers(ports: &mut [PortInfo]) {
+    let seen: HashSet<String> = ports
+        .iter()
+        .filter_map(|p| p.container.as_ref())
+        .map(|c| c.id.clone())
+        .collect();
+
+    let mut found: HashMap<String, String> = HashMap::new();
+    let mut failed: HashSet<String> = HashSet::new();
+    for container in ports.iter_mut().filter_map(|p| p.container.as_mut()) {
+        if container.name.is_some() || failed.contains(&container.id) {
+            continue;
+        }
+        if let Some(name) = found.get(&container.id) {
+            container.name = Some(name.clone());
+            continue;
+        }
+        match container::lookup_name(container.runtime, &container.id) {
+            Some(name) => {
+                found.insert(container.id.clone(), name.clone());
+                container.name = Some(name);
+            }
+            None => {
+                failed.insert(container.id.clone());
+            }
+        }
+    }
+
+    shared_resolver().update_container_names(&seen, found);
+}
+
+/// Scanner over the procfs tree selected by `PORTWATCHER_PROC_ROOT` (/proc by default)
+///
+/// Other network namespaces are found through the shared resolver's process cache.
+fn scanner() -> PortScanner {
+    let namespaces = refreshed_resolver(&HashSet::new()).pids_by_netns();
+    PortScanner::with_procfs(ProcFs::from_env()).with_namespaces(namespaces)
+}
+
+/// Listening and bound sockets on a port, optionally of a single protocol
+fn port_sockets(port: u16, protocol: Option<Protocol>) -> Result<Vec<RawSocketEntry>, String> {
+    let entries = scanner()
+        .scan_range(port, port, ScanMode::Listening)
+        .map_err(|e| e.to_string())?;
+
+    Ok(entries
+        .into_iter()
+        .filter(|entry| match protocol {
+            Some(protocol) => entry.protocol == protocol,
+            // Raw and ICMP "ports" are protocol numbers and echo IDs, not ports
+            None => entry.protocol.has_ports(),
+        })
+        .collect())
+}
+
+/// Process manager over the selected procfs tree, honouring the user's protected
+/// processes and recording to `kill_log`, with an optional SIGTERM grace period
+fn process_manager(kill_log: Option<&KillLog>, grace_ms: Option<u64>) -> ProcessManager {
+    let mut manager = ProcessManager::with_procfs(ProcFs::from_env())
+        .with_protection(ProtectionPolicy::from_config());
+    if let Some(kill_log) = kill_log {
+        manager = manager.with_kill_log(kill_log.clone());
+    }
+    match grace_ms {
+        Some(ms) => manager.with_grace_period(Duration::from_millis(ms)),
+        None => manager,
+    }
+}
+
+/// Get the most recent kill and signal attempts in `kill_log`, newest first (50 by default)
+///
+/// Recorded environments are left out; they are only needed to relaunch.
+pub fn get_kill_history(kill_log: Option<&KillLog>, limit: Option<usize>) -> Vec<KillHistoryEntry> {
+    kill_log
+        .map(|kill_log| kill_log.recent(limit.unwrap_or(50)))
+        .unwrap_or_default()
+        .into_iter()
+        .map(KillHistoryEntry::from)
+        .collect()
+}
+
+/// Start a killed process again, from what the kill history recorded of it
+///
+/// Waits up to `wait_ms` (5s by default) for it to listen again on the port it
+/// was killed for, and reports whether it did.
+pub fn relaunch(
+    kill_log: Option<&KillLog>,
+    kill_id: u64,
+    wait_ms: Option<u64>,
+) -> Result<RelaunchResult, String> {
+    let record = kill_log
+        .and_then(|kill_log| kill_log.find(kill_id))
+        .ok_or_else(|| RelaunchError::NotFound(kill_id).to_string())?;
+    let pid = crate::relaunch::relaunch(&record).map_err(|e| e.to_string())?;
+
+    let timeout = wait_ms.map_or(RELAUNCH_PORT_TIMEOUT, Duration::from_millis);
+    Ok(RelaunchResult {
+        kill_id,
+        pid,
+        port: record.port,
+        port_reacquired: record.port.map(|port| wait_for_port(port, pid, timeout)),
+    })
+}
+
+/// Poll until `pid` or one of its descendants listens on `port`, for at most `timeout`
+fn wait_for_port(port: u16, pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let owners: Vec<u32> = port_sockets(port, None)
+            .map(|entries| resolve_entries(entries, &HashSet::new()))
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|info| &info.owners)
+            .map(|owner| owner.pid)
+            .collect();
+
+        // e.g. npm starting node: the listener is a child of the relaunched process
+        let resolver = shared_resolver();
+        if owners
+            .iter()
+            .any(|&owner| resolver.get_ancestry(owner).iter().any(|p| p.pid == pid))
+        {
+            return true;
+        }
+        drop(resolver);
+
+        // Exited (e.g. crashed on startup) or out of time
+        if !ProcFs::from_env().pid_dir(pid).exists() || Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(RELAUNCH_POLL_INTERVAL);
+    }
 }
 
 /// Get built-in port presets
@@ -92,18 +549,23 @@ pub fn get_presets() -> Vec<PortPreset> {
 /// Get statistics for the system tray tooltip
 #[tauri::command]
 pub fn get_tray_stats() -> Result<TrayStats, String> {
-    let ports = get_ports()?;
+    let ports = get_ports(None)?;
 
     let tcp_count = ports.iter().filter(|p| p.protocol == Protocol::Tcp).count();
     let udp_count = ports.iter().filter(|p| p.protocol == Protocol::Udp).count();
+    let raw_count = ports.iter().filter(|p| !p.protocol.has_ports()).count();
+    let total_ports = ports.len() - raw_count;
+    let other_count = total_ports - tcp_count - udp_count;
 
-    // Count unique PIDs
-    let unique_pids: HashSet<u32> = ports.iter().map(|p| p.pid).collect();
+    // Count unique PIDs (hidden processes can't be told apart, so they're left out)
+    let unique_pids: HashSet<u32> = ports.iter().filter_map(|p| p.pid).collect();
 
     Ok(TrayStats {
-        total_ports: ports.len(),
+        total_ports,
         tcp_count,
         udp_count,
+        other_count,
+        raw_count,
         process_count: unique_pids.len(),
     })
 }
@@ -111,18 +573,232 @@ pub fn get_tray_stats() -> Result<TrayStats, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{KillRecord, TcpState};
 
     #[test]
     fn test_get_ports_returns_list() {
         // This test verifies get_ports doesn't panic
         // Actual ports depend on system state
-        let result = get_ports();
+        let result = get_ports(None);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_get_ports_all_mode() {
+        let result = get_ports(Some(ScanMode::All));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_get_port_connections() {
+        // Connect to a local listener and check the accepted side is reported
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let client = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let (_server, _) = listener.accept().unwrap();
+        let client_port = client.local_addr().unwrap().port();
+
+        let connections = get_port_connections(port).unwrap();
+        assert!(connections
+            .iter()
+            .any(|c| c.port == port && c.remote_port == Some(client_port)));
+    }
+
+    #[test]
+    fn test_resolve_entries_keeps_hidden_processes() {
+        // No process holds this inode, as with another user's unreadable fds
+        let entry = RawSocketEntry {
+            local_address: "0.0.0.0".to_string(),
+            local_port: 5432,
+            remote_address: "0.0.0.0".to_string(),
+            remote_port: 0,
+            inode: u64::MAX,
+            protocol: Protocol::Tcp,
+            family: crate::types::AddressFamily::Ipv4,
+            state: Some(TcpState::Listen),
+            tx_queue: 0,
+            rx_queue: 0,
+            uid: 0,
+            netns: None,
+        };
+
+        let ports = resolve_entries(vec![entry], &HashSet::new());
+
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].pid, None);
+        assert_eq!(ports[0].process_name, None);
+        assert_eq!(ports[0].uid, 0);
+        assert_eq!(ports[0].user, "root");
+    }
+
+    #[test]
+    fn test_scan_port_range_flags_stalled_backlog() {
+        // Connections nobody accepts stay in the listener's accept queue
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let _client = std::net::TcpStream::connect(("127.0.0.1", port)).unwrap();
+
+        let find = |ports: Vec<PortInfo>| {
+            ports
+                .into_iter()
+                .find(|p| p.port == port && p.state == Some(TcpState::Listen))
+                .unwrap()
+        };
+
+        let first = find(scan_port_range(port, port, None).unwrap());
+        assert_eq!(first.rx_queue, 1);
+        assert!(!first.backlog_stalled);
+
+        let second = find(scan_port_range(port, port, None).unwrap());
+        assert!(second.backlog_stalled);
+    }
+
+    #[test]
+    fn test_free_port_stops_holder() {
+        // A child inherits the listening socket (as its stdin) and is its only holder
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .stdin(std::os::fd::OwnedFd::from(listener))
+            .spawn()
+            .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("portwatcher-free-{}", std::process::id()));
+        let kill_log = KillLog::new(dir.join("kills.jsonl"));
+        let result = free_port(Some(&kill_log), port, Some(Protocol::Tcp), None, None).unwrap();
+        child.wait().unwrap();
+
+        assert_eq!(result.stopped.len(), 1);
+        assert_eq!(result.stopped[0].pid, child.id());
+        assert!(matches!(
+            result.stopped[0].result,
+            KillResult::Success { .. }
+        ));
+        assert!(result.port_free);
+        assert!(result.remaining.is_empty());
+
+        // Recorded under the port it was killed for, in the log it was given
+        let history = get_kill_history(Some(&kill_log), None);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].port, Some(port));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_kill_history_leaves_out_environment() {
+        let dir = std::env::temp_dir().join(format!("portwatcher-history-{}", std::process::id()));
+        let kill_log = KillLog::new(dir.join("kills.jsonl"));
+        kill_log
+            .append(&KillRecord {
+                id: 1,
+                timestamp: 0,
+                pid: 4242,
+                name: Some("node".to_string()),
+                cmdline: vec!["node".to_string(), "server.js".to_string()],
+                cwd: Some("/srv".to_string()),
+                environ: Some(
+                    [("API_TOKEN".to_string(), "secret".to_string())]
+                        .into_iter()
+                        .collect(),
+                ),
+                port: Some(3000),
+                signal: ProcessSignal::Sigterm,
+                elevated: false,
+                outcome: KillResult::ProcessNotFound { pid: 4242 },
+            })
+            .unwrap();
+
+        let history = get_kill_history(Some(&kill_log), None);
+        let json = serde_json::to_string(&history).unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(history[0].cmdline, vec!["node", "server.js"]);
+        assert!(!json.contains("environ"));
+        assert!(!json.contains("secret"));
+    }
+
+    #[test]
+    fn test_wait_for_port_finds_descendant() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .stdin(std::os::fd::OwnedFd::from(listener))
+            .spawn()
+            .unwrap();
+
+        // The holder is a child of this test process
+        let own_pid = std::process::id();
+        assert!(wait_for_port(port, own_pid, Duration::from_secs(1)));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(!wait_for_port(port, own_pid, Duration::ZERO));
+    }
+
+    #[test]
+    fn test_get_unix_sockets_resolves_owner() {
+        let dir = std::env::temp_dir().join(format!("portwatcher-cmd-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("owner.sock");
+        let _ = std::fs::remove_file(&path);
+        let _listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        let sockets = get_unix_sockets(None).unwrap();
+        let own = sockets
+            .iter()
+            .find(|s| s.path.as_deref() == path.to_str())
+            .cloned();
+
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(own.and_then(|s| s.pid), Some(std::process::id()));
+    }
+
+    #[test]
+    fn test_resolve_unix_entries_keeps_hidden_processes() {
+        let dir = std::env::temp_dir().join(format!("portwatcher-hidden-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hidden.sock");
+        let _ = std::fs::remove_file(&path);
+        let _listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        let uid = std::fs::metadata(&path).unwrap().uid();
+
+        // No process holds these inodes, as with another user's unreadable fds
+        let entry = |path: Option<String>, abstract_name: Option<String>| UnixSocketEntry {
+            path,
+            abstract_name,
+            socket_type: crate::types::UnixSocketType::Stream,
+            state: crate::types::UnixSocketState::Unconnected,
+            listening: true,
+            inode: u64::MAX,
+        };
+        let sockets = resolve_unix_entries(vec![
+            entry(path.to_str().map(String::from), None),
+            entry(None, Some("hidden".to_string())),
+        ]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(sockets.len(), 2);
+        assert!(sockets
+            .iter()
+            .all(|s| s.pid.is_none() && s.process_name.is_none()));
+        assert_eq!(sockets[1].abstract_name.as_deref(), Some("hidden"));
+        assert_eq!(sockets[0].user, Some(ProcessResolver::get_username(uid)));
+        assert_eq!(sockets[1].user, None);
+    }
+
+    #[test]
+    fn test_get_process_details() {
+        let details = get_process_details(std::process::id()).unwrap();
+        assert_eq!(details.pid, std::process::id());
+
+        assert!(get_process_details(999999999).is_err());
+    }
+
     #[test]
     fn test_kill_process_invalid() {
-        let result = kill_process(999999999);
+        let result = kill_process(None, 999999999, None, None, None);
         matches!(result, KillResult::ProcessNotFound { .. });
     }
 }
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// User settings, read from $XDG_CONFIG_HOME/portwatcher/config.json
+///
+/// Every field is optional in the file; missing ones take their default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Process names that must never be signalled, on top of the built-in ones
+    ///
+    /// Matched against the process name and the file name of its executable
+    /// (e.g. "postgres" or "gnome-remote-desktop-daemon").
+    pub protected_processes: Vec<String>,
+}
+
+impl Config {
+    /// Load the user's config, falling back to the defaults
+    ///
+    /// A missing or malformed file is not an error: PortWatcher still has to
+    /// start, and the built-in protections don't depend on it.
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    /// Load a config file, None if it can't be read or parsed
+    pub fn load_from(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Location of the config file
+    pub fn path() -> Option<PathBuf> {
+        xdg_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join("portwatcher/config.json"))
+    }
+}
+
+/// An XDG base directory: `$var` if set to an absolute path, `$HOME/<fallback>` otherwise
+pub(crate) fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    match std::env::var_os(var).map(PathBuf::from) {
+        Some(dir) if dir.is_absolute() => Some(dir),
+        _ => std::env::var_os("HOME").map(|home| Path::new(&home).join(fallback)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_from_file() {
+        let path =
+            std::env::temp_dir().join(format!("portwatcher-config-{}.json", std::process::id()));
+
+        fs::write(&path, r#"{"protected_processes": ["postgres"]}"#).unwrap();
+        assert_eq!(
+            Config::load_from(&path).unwrap().protected_processes,
+            vec!["postgres"]
+        );
+
+        // Unknown keys are ignored, missing ones default
+        fs::write(&path, r#"{"theme": "dark"}"#).unwrap();
+        assert_eq!(Config::load_from(&path), Some(Config::default()));
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(Config::load_from(&path), None);
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(Config::load_from(&path), None);
+    }
+}
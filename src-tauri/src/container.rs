@@ -0,0 +1,321 @@
+use crate::types::ContainerRuntime;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long a name lookup may spend on a runtime socket in total
+const RUNTIME_SOCKET_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Detect the container a process belongs to from its /proc/[pid]/cgroup content
+///
+/// Returns the runtime and the full container ID. Recognized layouts:
+/// - cgroup v2 systemd scopes: `docker-<id>.scope`, `libpod-<id>.scope`,
+///   `cri-containerd-<id>.scope`, `crio-<id>.scope`
+/// - cgroup v1 / cgroupfs paths: `/docker/<id>`, `/libpod_parent/libpod-<id>`,
+///   `/kubepods/<qos>/pod<uid>/<id>`
+pub fn parse_cgroup(content: &str) -> Option<(ContainerRuntime, String)> {
+    for line in content.lines() {
+        // Format: hierarchy-ID:controller-list:cgroup-path
+        let Some(path) = line.splitn(3, ':').nth(2) else {
+            continue;
+        };
+
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        // The innermost match wins (rootless podman nests a "container" cgroup below it)
+        for (i, segment) in segments.iter().enumerate().rev() {
+            let parent = if i > 0 { segments[i - 1] } else { "" };
+            if let Some(found) = parse_cgroup_segment(segment, parent) {
+                return Some(found);
+            }
+        }
+    }
+
+    None
+}
+
+/// Match a single cgroup path segment against known runtime naming schemes
+fn parse_cgroup_segment(segment: &str, parent: &str) -> Option<(ContainerRuntime, String)> {
+    let scope = segment.strip_suffix(".scope").unwrap_or(segment);
+
+    let prefixes = [
+        ("docker-", ContainerRuntime::Docker),
+        ("libpod-", ContainerRuntime::Podman),
+        ("cri-containerd-", ContainerRuntime::Containerd),
+        ("crio-", ContainerRuntime::CriO),
+    ];
+
+    for (prefix, runtime) in prefixes {
+        if let Some(id) = scope.strip_prefix(prefix) {
+            // conmon scopes ("libpod-conmon-<id>") fail the ID check and are skipped
+            if is_container_id(id) {
+                return Some((runtime, id.to_string()));
+            }
+        }
+    }
+
+    // cgroupfs driver: the bare ID sits below a runtime- or pod-named directory
+    if is_container_id(segment) {
+        if parent == "docker" {
+            return Some((ContainerRuntime::Docker, segment.to_string()));
+        }
+        if parent.starts_with("pod") {
+            return Some((ContainerRuntime::Containerd, segment.to_string()));
+        }
+    }
+
+    None
+}
+
+/// Container IDs are 64 lowercase hex characters
+fn is_container_id(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Look up a container's name through its runtime's local API socket
+///
+/// Only Docker and Podman expose a Docker-compatible HTTP API on a Unix socket.
+/// Returns None if no socket is reachable or the container is unknown.
+pub fn lookup_name(runtime: ContainerRuntime, id: &str) -> Option<String> {
+    runtime_sockets(runtime)
+        .iter()
+        .filter(|path| path.exists())
+        .find_map(|path| RuntimeClient::new(path).container_name(id))
+}
+
+/// Candidate API sockets for a runtime, in order of preference
+fn runtime_sockets(runtime: ContainerRuntime) -> Vec<PathBuf> {
+    match runtime {
+        ContainerRuntime::Docker => {
+            let mut sockets = Vec::new();
+            if let Some(path) = std::env::var("DOCKER_HOST")
+                .ok()
+                .and_then(|host| host.strip_prefix("unix://").map(PathBuf::from))
+            {
+                sockets.push(path);
+            }
+            sockets.push(PathBuf::from("/var/run/docker.sock"));
+            sockets
+        }
+        ContainerRuntime::Podman => {
+            let mut sockets = vec![PathBuf::from("/run/podman/podman.sock")];
+            if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+                sockets.push(Path::new(&runtime_dir).join("podman/podman.sock"));
+            }
+            sockets
+        }
+        ContainerRuntime::Containerd | ContainerRuntime::CriO => Vec::new(),
+    }
+}
+
+/// Minimal client for the Docker-compatible REST API over a Unix socket
+pub struct RuntimeClient {
+    socket_path: PathBuf,
+}
+
+impl RuntimeClient {
+    /// Create a client for the API socket at `socket_path`
+    pub fn new(socket_path: &Path) -> Self {
+        Self {
+            socket_path: socket_path.to_path_buf(),
+        }
+    }
+
+    /// Fetch a container's name via `GET /containers/<id>/json`
+    pub fn container_name(&self, id: &str) -> Option<String> {
+        // Never interpolate anything but a plain ID into the request path
+        if !id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return None;
+        }
+
+        let deadline = Instant::now() + RUNTIME_SOCKET_TIMEOUT;
+        let mut stream = UnixStream::connect(&self.socket_path).ok()?;
+        stream.set_write_timeout(Some(RUNTIME_SOCKET_TIMEOUT)).ok()?;
+
+        // HTTP/1.0 so the server closes the connection and never chunks the body
+        write!(
+            stream,
+            "GET /containers/{}/json HTTP/1.0\r\nHost: localhost\r\n\r\n",
+            id
+        )
+        .ok()?;
+
+        // A per-read timeout alone would let a runtime trickling bytes stall us indefinitely
+        let mut response = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let remaining = deadline.checked_duration_since(Instant::now())?;
+            if remaining.is_zero() {
+                return None;
+            }
+            stream.set_read_timeout(Some(remaining)).ok()?;
+            match stream.read(&mut buf).ok()? {
+                0 => break,
+                n => response.extend_from_slice(&buf[..n]),
+            }
+        }
+
+        Self::parse_name_response(&String::from_utf8_lossy(&response))
+    }
+
+    /// Extract the container name from an HTTP response to /containers/<id>/json
+    fn parse_name_response(response: &str) -> Option<String> {
+        let (head, body) = response.split_once("\r\n\r\n")?;
+        let status_line = head.lines().next()?;
+        if status_line.split_whitespace().nth(1) != Some("200") {
+            return None;
+        }
+
+        let json: serde_json::Value = serde_json::from_str(body).ok()?;
+        let name = json.get("Name")?.as_str()?;
+
+        // Docker reports names with a leading slash ("/myapp")
+        Some(name.trim_start_matches('/').to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+
+    const ID: &str = "3f4e8c1b2a9d7e6f5c4b3a2918f7e6d5c4b3a2918f7e6d5c4b3a2918f7e6d5c4";
+
+    #[test]
+    fn test_parse_cgroup_docker_v2() {
+        let content = format!("0::/system.slice/docker-{}.scope\n", ID);
+        assert_eq!(
+            parse_cgroup(&content),
+            Some((ContainerRuntime::Docker, ID.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_cgroup_docker_v1() {
+        let content = format!("12:memory:/docker/{}\n11:cpu:/docker/{}\n", ID, ID);
+        assert_eq!(
+            parse_cgroup(&content),
+            Some((ContainerRuntime::Docker, ID.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_cgroup_rootless_podman() {
+        let content = format!(
+            "0::/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{}.scope/container\n",
+            ID
+        );
+        assert_eq!(
+            parse_cgroup(&content),
+            Some((ContainerRuntime::Podman, ID.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_cgroup_kubernetes_containerd() {
+        let content = format!(
+            "0::/kubepods.slice/kubepods-besteffort.slice/kubepods-besteffort-pod1234.slice/cri-containerd-{}.scope\n",
+            ID
+        );
+        assert_eq!(
+            parse_cgroup(&content),
+            Some((ContainerRuntime::Containerd, ID.to_string()))
+        );
+
+        let content = format!("4:pids:/kubepods/besteffort/pod1234-abcd/{}\n", ID);
+        assert_eq!(
+            parse_cgroup(&content),
+            Some((ContainerRuntime::Containerd, ID.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_cgroup_crio() {
+        let content = format!("0::/kubepods.slice/crio-{}.scope\n", ID);
+        assert_eq!(
+            parse_cgroup(&content),
+            Some((ContainerRuntime::CriO, ID.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_cgroup_host_process() {
+        let content = "0::/user.slice/user-1000.slice/session-2.scope\n";
+        assert_eq!(parse_cgroup(content), None);
+
+        // conmon runs outside the container it monitors
+        let content = format!("0::/machine.slice/libpod-conmon-{}.scope\n", ID);
+        assert_eq!(parse_cgroup(&content), None);
+    }
+
+    #[test]
+    fn test_container_name_from_stub_socket() {
+        let dir = std::env::temp_dir().join(format!("portwatcher-runtime-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let socket_path = dir.join("docker.sock");
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        // Stub runtime: answers a single request with a canned inspect response
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let n = stream.read(&mut request).unwrap();
+            let request = String::from_utf8_lossy(&request[..n]).to_string();
+            let body = r#"{"Id":"abc","Name":"/my-postgres"}"#;
+            write!(
+                stream,
+                "HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n{}",
+                body
+            )
+            .unwrap();
+            request
+        });
+
+        let name = RuntimeClient::new(&socket_path).container_name("abc123");
+        let request = server.join().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(request.starts_with("GET /containers/abc123/json HTTP/1.0\r\n"));
+        assert_eq!(name.as_deref(), Some("my-postgres"));
+    }
+
+    #[test]
+    fn test_container_name_gives_up_on_silent_runtime() {
+        let dir = std::env::temp_dir().join(format!("portwatcher-silent-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let socket_path = dir.join("docker.sock");
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        // Stub runtime: accepts the connection and never answers
+        let (release, hold) = std::sync::mpsc::channel::<()>();
+        let server = std::thread::spawn(move || {
+            let (_stream, _) = listener.accept().unwrap();
+            let _ = hold.recv();
+        });
+
+        let started = Instant::now();
+        let name = RuntimeClient::new(&socket_path).container_name("abc123");
+        let elapsed = started.elapsed();
+        release.send(()).unwrap();
+        server.join().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(name, None);
+        assert!(elapsed < RUNTIME_SOCKET_TIMEOUT * 4, "took {:?}", elapsed);
+    }
+
+    #[test]
+    fn test_parse_name_response_not_found() {
+        let response = "HTTP/1.0 404 Not Found\r\n\r\n{\"message\":\"No such container\"}";
+        assert_eq!(RuntimeClient::parse_name_response(response), None);
+    }
+
+    #[test]
+    fn test_container_name_rejects_unsafe_id() {
+        let client = RuntimeClient::new(Path::new("/nonexistent.sock"));
+        assert_eq!(client.container_name("../../info"), None);
+    }
+}
@@ -0,0 +1,322 @@
+use crate::process_manager::ProcessManager;
+use crate::protection::ProtectionPolicy;
+use crate::types::{
+    HelperRequestError, KillTarget, ProcessIdentity, ProcessKillResult, ProcessSignal,
+};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Environment variable pointing debug builds at a locally built helper
+///
+/// Release builds ignore it: pkexec would run whatever it names, under polkit's
+/// generic prompt rather than the PortWatcher action.
+#[cfg(debug_assertions)]
+pub const HELPER_PATH_ENV: &str = "PORTWATCHER_HELPER";
+
+/// Where packages install the helper; the polkit action only covers this path
+const DEFAULT_HELPER_PATH: &str = "/usr/libexec/portwatcher/portwatcher-helper";
+
+/// Exit status of the helper for a request it refuses to parse (EX_USAGE)
+///
+/// pkexec itself exits with 126 and 127, which the helper never uses.
+pub const EXIT_USAGE: i32 = 64;
+
+/// Longest grace period the helper accepts, so a root process never lingers
+pub const MAX_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Path of the privileged helper
+///
+/// Always the installed one in release builds; debug builds honour `PORTWATCHER_HELPER`.
+pub fn helper_path() -> PathBuf {
+    #[cfg(debug_assertions)]
+    if let Some(path) = std::env::var_os(HELPER_PATH_ENV).filter(|path| !path.is_empty()) {
+        return PathBuf::from(path);
+    }
+    PathBuf::from(DEFAULT_HELPER_PATH)
+}
+
+/// What PortWatcher may ask its privileged helper (src/bin/portwatcher-helper.rs) to do
+///
+/// Only these two requests exist, and both name processes by identity, so the
+/// helper can't be used to signal a process that reused a PID. `protected` are
+/// the user's configured protected process names, which root can't read from
+/// the user's config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HelperRequest {
+    /// Kill processes as `ProcessManager::kill_all` does
+    Kill {
+        targets: Vec<ProcessIdentity>,
+        grace_period: Duration,
+        escalate: bool,
+        protected: Vec<String>,
+    },
+    /// Send one signal to one process
+    Signal {
+        target: ProcessIdentity,
+        signal: ProcessSignal,
+        protected: Vec<String>,
+    },
+}
+
+/// Prefix of the arguments passing protected process names, before the request
+const PROTECT_ARG: &str = "--protect=";
+
+impl HelperRequest {
+    /// Command-line arguments passing this request to the helper
+    pub fn to_args(&self) -> Vec<String> {
+        let target_arg = |t: &ProcessIdentity| format!("{}:{}", t.pid, t.start_time);
+        let mut args: Vec<String> = self
+            .protected()
+            .iter()
+            .map(|name| format!("{}{}", PROTECT_ARG, name))
+            .collect();
+        match self {
+            HelperRequest::Kill {
+                targets,
+                grace_period,
+                escalate,
+                ..
+            } => {
+                args.extend([
+                    "kill".to_string(),
+                    grace_period.as_millis().to_string(),
+                    u8::from(*escalate).to_string(),
+                ]);
+                args.extend(targets.iter().map(target_arg));
+            }
+            HelperRequest::Signal { target, signal, .. } => args.extend([
+                "signal".to_string(),
+                signal.kill_name().to_string(),
+                target_arg(target),
+            ]),
+        }
+        args
+    }
+
+    /// Parse the helper's command-line arguments (without the program name)
+    pub fn parse(args: &[String]) -> Result<Self, HelperRequestError> {
+        let options = args
+            .iter()
+            .take_while(|arg| arg.starts_with(PROTECT_ARG))
+            .count();
+        let protected: Vec<String> = args[..options]
+            .iter()
+            .map(|arg| arg[PROTECT_ARG.len()..].to_string())
+            .collect();
+
+        match &args[options..] {
+            [command, grace_ms, escalate, targets @ ..] if command == "kill" => {
+                if targets.is_empty() {
+                    return Err(HelperRequestError::Usage);
+                }
+                let grace_period = grace_ms
+                    .parse()
+                    .map(Duration::from_millis)
+                    .ok()
+                    .filter(|grace| *grace <= MAX_GRACE_PERIOD)
+                    .ok_or_else(|| {
+                        HelperRequestError::InvalidValue("grace period", grace_ms.clone())
+                    })?;
+                let escalate = match escalate.as_str() {
+                    "0" => false,
+                    "1" => true,
+                    _ => {
+                        return Err(HelperRequestError::InvalidValue(
+                            "escalation flag",
+                            escalate.clone(),
+                        ))
+                    }
+                };
+                Ok(HelperRequest::Kill {
+                    targets: targets
+                        .iter()
+                        .map(|t| Self::parse_target(t))
+                        .collect::<Result<_, _>>()?,
+                    grace_period,
+                    escalate,
+                    protected,
+                })
+            }
+            [command, signal, target] if command == "signal" => Ok(HelperRequest::Signal {
+                target: Self::parse_target(target)?,
+                signal: ProcessSignal::from_kill_name(signal)
+                    .ok_or_else(|| HelperRequestError::InvalidSignal(signal.clone()))?,
+                protected,
+            }),
+            _ => Err(HelperRequestError::Usage),
+        }
+    }
+
+    /// Process names the user protected, on top of the built-in protections
+    pub fn protected(&self) -> &[String] {
+        match self {
+            HelperRequest::Kill { protected, .. } | HelperRequest::Signal { protected, .. } => {
+                protected
+            }
+        }
+    }
+
+    /// Carry the request out with the current privileges
+    ///
+    /// Returns one result per process. The built-in and `protected` protections
+    /// still apply.
+    pub fn run(&self) -> Vec<ProcessKillResult> {
+        let manager = ProcessManager::new()
+            .with_protection(ProtectionPolicy::new().with_names(self.protected().iter().cloned()));
+        match self {
+            HelperRequest::Kill {
+                targets,
+                grace_period,
+                escalate,
+                ..
+            } => {
+                let targets: Vec<KillTarget> = targets
+                    .iter()
+                    .map(|t| KillTarget {
+                        pid: t.pid,
+                        start_time: Some(t.start_time),
+                        port: None,
+                    })
+                    .collect();
+                manager
+                    .with_grace_period(*grace_period)
+                    .with_escalation(*escalate)
+                    .kill_all(&targets)
+            }
+            HelperRequest::Signal { target, signal, .. } => vec![ProcessKillResult {
+                pid: target.pid,
+                result: manager.send_signal(target.pid, *signal, Some(target.start_time)),
+            }],
+        }
+    }
+
+    /// Parse "<pid>:<start_time>"
+    fn parse_target(arg: &str) -> Result<ProcessIdentity, HelperRequestError> {
+        let (pid, start_time) = arg
+            .split_once(':')
+            .and_then(|(pid, start)| Some((pid.parse().ok()?, start.parse().ok()?)))
+            .ok_or_else(|| HelperRequestError::InvalidTarget(arg.to_string()))?;
+        Ok(ProcessIdentity { pid, start_time })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{KillResult, KillStage, ProtectionReason};
+    use std::process::Command;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        let requests = [
+            HelperRequest::Kill {
+                targets: vec![
+                    ProcessIdentity {
+                        pid: 4242,
+                        start_time: 1000,
+                    },
+                    ProcessIdentity {
+                        pid: 4243,
+                        start_time: 1001,
+                    },
+                ],
+                grace_period: Duration::from_millis(1500),
+                escalate: false,
+                protected: vec!["postgres".to_string(), "my server".to_string()],
+            },
+            HelperRequest::Signal {
+                target: ProcessIdentity {
+                    pid: 4242,
+                    start_time: 1000,
+                },
+                signal: ProcessSignal::Sighup,
+                protected: Vec::new(),
+            },
+        ];
+        for request in requests {
+            assert_eq!(HelperRequest::parse(&request.to_args()).unwrap(), request);
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_other_requests() {
+        for bad in [
+            &[][..],
+            &["exec", "/bin/sh"],
+            &["kill", "3000", "1"],
+            &["kill", "3000", "1", "4242"],
+            &["kill", "3000", "yes", "4242:1000"],
+            &["kill", "3600000", "1", "4242:1000"],
+            &["signal", "SEGV", "4242:1000"],
+            &["signal", "HUP", "4242:1000", "4243:1001"],
+            &["--protect=postgres"],
+            &["signal", "HUP", "--protect=postgres", "4242:1000"],
+        ] {
+            assert!(HelperRequest::parse(&args(bad)).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_run_kill_checks_identity() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let identity = ProcessManager::new().identify(child.id()).unwrap();
+        let stale = ProcessIdentity {
+            start_time: identity.start_time + 1,
+            ..identity
+        };
+
+        let signal = HelperRequest::Signal {
+            target: stale,
+            signal: ProcessSignal::Sigkill,
+            protected: Vec::new(),
+        };
+        assert!(matches!(
+            signal.run()[0].result,
+            KillResult::IdentityMismatch { .. }
+        ));
+
+        let kill = HelperRequest::Kill {
+            targets: vec![identity],
+            grace_period: Duration::from_secs(1),
+            escalate: true,
+            protected: Vec::new(),
+        };
+        let results = kill.run();
+        child.wait().unwrap();
+        assert_eq!(results[0].pid, identity.pid);
+        assert!(matches!(
+            results[0].result,
+            KillResult::Success {
+                stage: KillStage::Sigterm
+            }
+        ));
+    }
+
+    #[test]
+    fn test_run_applies_passed_protections() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let identity = ProcessManager::new().identify(child.id()).unwrap();
+
+        let kill = HelperRequest::Kill {
+            targets: vec![identity],
+            grace_period: Duration::from_secs(1),
+            escalate: true,
+            protected: vec!["sleep".to_string()],
+        };
+        let results = kill.run();
+        assert!(matches!(
+            results[0].result,
+            KillResult::Protected {
+                reason: ProtectionReason::Configured,
+                ..
+            }
+        ));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+use crate::config::xdg_dir;
+use crate::types::KillRecord;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+
+/// Size past which the log is cut down to its newest records
+pub const DEFAULT_MAX_SIZE: u64 = 4 * 1024 * 1024;
+
+/// History of kill attempts, one JSON record per line
+///
+/// Lives in $XDG_STATE_HOME/portwatcher/kills.jsonl by default. Records are
+/// appended, so the newest is last; once the file outgrows its maximum size the
+/// oldest are dropped. They hold process environments, which may contain
+/// secrets, so the file is only readable by its owner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KillLog {
+    path: PathBuf,
+    max_size: u64,
+}
+
+impl KillLog {
+    /// Use the log file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_size: DEFAULT_MAX_SIZE,
+        }
+    }
+
+    /// Keep the file under roughly `max_size` bytes
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// The log in the user's XDG state directory, None without a home directory
+    pub fn user() -> Option<Self> {
+        xdg_dir("XDG_STATE_HOME", ".local/state")
+            .map(|dir| Self::new(dir.join("portwatcher/kills.jsonl")))
+    }
+
+    /// Location of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Add a record, creating the file and its directory if needed
+    pub fn append(&self, record: &KillRecord) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        // A single write per record, so concurrent appends don't interleave
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o600)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+
+        if file.metadata()?.len() > self.max_size {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite the file with only its newest records, filling half the maximum size
+    ///
+    /// Halving leaves room for many appends before the next rewrite. The new file
+    /// replaces the old one in a single rename, so readers never see it partial.
+    fn compact(&self) -> io::Result<()> {
+        let content = fs::read_to_string(&self.path)?;
+
+        let budget = self.max_size / 2;
+        let mut kept = 0;
+        let mut start = content.len();
+        for line in content.lines().rev() {
+            let size = line.len() as u64 + 1;
+            if kept + size > budget {
+                break;
+            }
+            kept += size;
+            start = start.saturating_sub(size as usize);
+        }
+
+        let tmp = self.path.with_extension("jsonl.tmp");
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&tmp)?
+            .write_all(&content.as_bytes()[start..])?;
+        fs::rename(tmp, &self.path)
+    }
+
+    /// The record with the given id
+    pub fn find(&self, id: u64) -> Option<KillRecord> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<KillRecord>(line).ok())
+            .find(|record| record.id == id)
+    }
+
+    /// The last `limit` records, newest first
+    ///
+    /// A missing file is an empty history; lines that don't parse are skipped.
+    pub fn recent(&self, limit: usize) -> Vec<KillRecord> {
+        let Ok(content) = fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+
+        content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(limit)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{KillResult, KillStage, ProcessSignal};
+    use std::collections::BTreeMap;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn record(pid: u32) -> KillRecord {
+        KillRecord {
+            id: pid as u64,
+            timestamp: 1_700_000_000_000,
+            pid,
+            name: Some("node".to_string()),
+            cmdline: vec!["node".to_string(), "server.js".to_string()],
+            cwd: Some("/home/dev/app".to_string()),
+            environ: Some(BTreeMap::from([("PORT".to_string(), "3000".to_string())])),
+            port: Some(3000),
+            signal: ProcessSignal::Sigterm,
+            elevated: false,
+            outcome: KillResult::Success {
+                stage: KillStage::Sigterm,
+            },
+        }
+    }
+
+    #[test]
+    fn test_append_and_read_back() {
+        let dir = std::env::temp_dir().join(format!("portwatcher-kill-log-{}", std::process::id()));
+        let log = KillLog::new(dir.join("state/kills.jsonl"));
+        assert!(log.recent(5).is_empty());
+
+        for pid in 100..107 {
+            log.append(&record(pid)).unwrap();
+        }
+        // A torn or foreign line doesn't hide the rest
+        OpenOptions::new()
+            .append(true)
+            .open(log.path())
+            .unwrap()
+            .write_all(b"{\"timestamp\":\n")
+            .unwrap();
+
+        let pids: Vec<u32> = log.recent(5).iter().map(|r| r.pid).collect();
+        assert_eq!(pids, vec![106, 105, 104, 103, 102]);
+        assert_eq!(log.recent(5)[0].port, Some(3000));
+
+        assert_eq!(log.find(101).map(|r| r.pid), Some(101));
+        assert!(log.find(999).is_none());
+
+        // Environments may hold secrets
+        let mode = fs::metadata(log.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_oldest_records_are_dropped() {
+        let dir = std::env::temp_dir().join(format!("portwatcher-kill-cap-{}", std::process::id()));
+        let record_size = serde_json::to_string(&record(100)).unwrap().len() as u64 + 1;
+        let log = KillLog::new(dir.join("kills.jsonl")).with_max_size(record_size * 10);
+
+        for pid in 100..150 {
+            log.append(&record(pid)).unwrap();
+        }
+
+        let size = fs::metadata(log.path()).unwrap().len();
+        assert!(size <= record_size * 10, "log grew to {} bytes", size);
+        let pids: Vec<u32> = log.recent(3).iter().map(|r| r.pid).collect();
+        assert_eq!(pids, vec![149, 148, 147]);
+        assert!(log.find(100).is_none());
+
+        let mode = fs::metadata(log.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
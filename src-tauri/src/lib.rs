@@ -1,11 +1,26 @@
+pub mod backlog;
+#[cfg(feature = "app")]
 pub mod commands;
+pub mod config;
+pub mod container;
+pub mod helper;
+pub mod kill_log;
+pub mod pidfd;
 pub mod port_scanner;
 pub mod presets;
 pub mod process_manager;
 pub mod process_resolver;
+pub mod procfs;
+pub mod protection;
+pub mod relaunch;
+pub mod sock_diag;
 pub mod types;
 
 // Re-export commands for easy access in main.rs
+#[cfg(feature = "app")]
 pub use commands::{
-    get_ports, get_presets, get_tray_stats, kill_process, kill_process_elevated, scan_port_range,
+    free_port, get_kill_history, get_port_connections, get_ports, get_presets,
+    get_process_ancestry, get_process_details, get_tray_stats, get_unix_sockets, kill_process,
+    kill_process_elevated, kill_process_group, kill_process_tree, kill_processes,
+    kill_processes_elevated, relaunch, scan_port_range, send_signal, send_signal_elevated,
 };
@@ -1,117 +1,346 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::collections::HashSet;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use tauri::{
     menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Emitter, Manager,
+    AppHandle, Emitter, Listener, Manager, Runtime, State,
 };
 
-use portwatcher_lib::port_scanner::PortScanner;
+use portwatcher_lib::commands;
+use portwatcher_lib::kill_log::KillLog;
 use portwatcher_lib::presets::get_builtin_presets;
-use portwatcher_lib::process_manager::ProcessManager;
-use portwatcher_lib::process_resolver::ProcessResolver;
-use portwatcher_lib::types::{KillResult, PortInfo, PortPreset, Protocol, TrayStats};
+use portwatcher_lib::types::{
+    FreePortResult, KillHistoryEntry, KillResult, KillStage, KillTarget, PortInfo, PortPreset,
+    ProcessDetails, ProcessInfo, ProcessKillResult, ProcessSignal, Protocol, RelaunchResult,
+    ScanMode, TrayStats, UnixSocketInfo,
+};
 
-/// Get all listening ports with their process information
-#[tauri::command]
-fn get_ports() -> Result<Vec<PortInfo>, String> {
-    let raw_entries = PortScanner::scan_all().map_err(|e| e.to_string())?;
-    let resolver = ProcessResolver::new();
-
-    let mut ports: Vec<PortInfo> = raw_entries
-        .into_iter()
-        .filter_map(|entry| {
-            let process_info = resolver.resolve(entry.inode)?;
-            Some(PortInfo {
-                pid: process_info.pid,
-                process_name: process_info.name,
-                port: entry.local_port,
-                protocol: entry.protocol,
-                address: entry.local_address,
-                user: process_info.user,
-            })
-        })
-        .collect();
+/// Number of kills listed in the tray's "Recently Killed" submenu
+const RECENT_KILLS: usize = 5;
 
-    ports.sort_by_key(|p| p.port);
-    Ok(ports)
+/// Event emitted once a command has added to the kill history
+const KILLS_UPDATED: &str = "kills-updated";
+
+/// Get all ports with their process information
+#[tauri::command]
+fn get_ports(mode: Option<ScanMode>) -> Result<Vec<PortInfo>, String> {
+    commands::get_ports(mode)
 }
 
+// Kills wait out grace periods and pkexec prompts, and relaunch waits for the
+// port, so these commands run on the async runtime's pool, not the main thread.
+
 /// Kill a process by PID
-#[tauri::command]
-fn kill_process(pid: u32) -> KillResult {
-    ProcessManager::kill(pid)
+#[tauri::command(async)]
+fn kill_process(
+    app: AppHandle,
+    kill_log: State<'_, Option<KillLog>>,
+    pid: u32,
+    start_time: Option<u64>,
+    grace_ms: Option<u64>,
+    port: Option<u16>,
+) -> KillResult {
+    let result = commands::kill_process(kill_log.inner().as_ref(), pid, start_time, grace_ms, port);
+    let _ = app.emit(KILLS_UPDATED, ());
+    result
 }
 
 /// Kill a process by PID using elevated privileges (pkexec)
-#[tauri::command]
-fn kill_process_elevated(pid: u32) -> KillResult {
-    ProcessManager::kill_elevated(pid)
+#[tauri::command(async)]
+fn kill_process_elevated(
+    app: AppHandle,
+    kill_log: State<'_, Option<KillLog>>,
+    pid: u32,
+    start_time: Option<u64>,
+    grace_ms: Option<u64>,
+    port: Option<u16>,
+) -> KillResult {
+    let result =
+        commands::kill_process_elevated(kill_log.inner().as_ref(), pid, start_time, grace_ms, port);
+    let _ = app.emit(KILLS_UPDATED, ());
+    result
+}
+
+/// Kill several processes at once
+#[tauri::command(async)]
+fn kill_processes(
+    app: AppHandle,
+    kill_log: State<'_, Option<KillLog>>,
+    targets: Vec<KillTarget>,
+    grace_ms: Option<u64>,
+) -> Vec<ProcessKillResult> {
+    let result = commands::kill_processes(kill_log.inner().as_ref(), targets, grace_ms);
+    let _ = app.emit(KILLS_UPDATED, ());
+    result
+}
+
+/// Kill several processes using elevated privileges (one pkexec prompt)
+#[tauri::command(async)]
+fn kill_processes_elevated(
+    app: AppHandle,
+    kill_log: State<'_, Option<KillLog>>,
+    targets: Vec<KillTarget>,
+    grace_ms: Option<u64>,
+) -> Vec<ProcessKillResult> {
+    let result = commands::kill_processes_elevated(kill_log.inner().as_ref(), targets, grace_ms);
+    let _ = app.emit(KILLS_UPDATED, ());
+    result
+}
+
+/// Send a signal (SIGINT, SIGHUP, SIGSTOP, SIGCONT...) to a process
+#[tauri::command(async)]
+fn send_signal(
+    app: AppHandle,
+    kill_log: State<'_, Option<KillLog>>,
+    pid: u32,
+    signal: ProcessSignal,
+    start_time: Option<u64>,
+) -> KillResult {
+    let result = commands::send_signal(kill_log.inner().as_ref(), pid, signal, start_time);
+    let _ = app.emit(KILLS_UPDATED, ());
+    result
+}
+
+/// Send a signal to a process using elevated privileges (pkexec)
+#[tauri::command(async)]
+fn send_signal_elevated(
+    app: AppHandle,
+    kill_log: State<'_, Option<KillLog>>,
+    pid: u32,
+    signal: ProcessSignal,
+    start_time: Option<u64>,
+) -> KillResult {
+    let result = commands::send_signal_elevated(kill_log.inner().as_ref(), pid, signal, start_time);
+    let _ = app.emit(KILLS_UPDATED, ());
+    result
+}
+
+/// Stop every process holding a port and check it was released
+#[tauri::command(async)]
+fn free_port(
+    app: AppHandle,
+    kill_log: State<'_, Option<KillLog>>,
+    port: u16,
+    protocol: Option<Protocol>,
+    grace_ms: Option<u64>,
+    escalate: Option<bool>,
+) -> Result<FreePortResult, String> {
+    let result = commands::free_port(
+        kill_log.inner().as_ref(),
+        port,
+        protocol,
+        grace_ms,
+        escalate,
+    );
+    let _ = app.emit(KILLS_UPDATED, ());
+    result
+}
+
+/// Kill a process and all of its descendants
+#[tauri::command(async)]
+fn kill_process_tree(
+    app: AppHandle,
+    kill_log: State<'_, Option<KillLog>>,
+    pid: u32,
+    grace_ms: Option<u64>,
+) -> Vec<ProcessKillResult> {
+    let result = commands::kill_process_tree(kill_log.inner().as_ref(), pid, grace_ms);
+    let _ = app.emit(KILLS_UPDATED, ());
+    result
+}
+
+/// Kill every process in a process's group
+#[tauri::command(async)]
+fn kill_process_group(
+    app: AppHandle,
+    kill_log: State<'_, Option<KillLog>>,
+    pid: u32,
+    grace_ms: Option<u64>,
+) -> Vec<ProcessKillResult> {
+    let result = commands::kill_process_group(kill_log.inner().as_ref(), pid, grace_ms);
+    let _ = app.emit(KILLS_UPDATED, ());
+    result
 }
 
 /// Scan a specific port range
 #[tauri::command]
-fn scan_port_range(start: u16, end: u16) -> Result<Vec<PortInfo>, String> {
-    let raw_entries = PortScanner::scan_range(start, end).map_err(|e| e.to_string())?;
-    let resolver = ProcessResolver::new();
-
-    let mut ports: Vec<PortInfo> = raw_entries
-        .into_iter()
-        .filter_map(|entry| {
-            let process_info = resolver.resolve(entry.inode)?;
-            Some(PortInfo {
-                pid: process_info.pid,
-                process_name: process_info.name,
-                port: entry.local_port,
-                protocol: entry.protocol,
-                address: entry.local_address,
-                user: process_info.user,
-            })
-        })
-        .collect();
+fn scan_port_range(
+    start: u16,
+    end: u16,
+    mode: Option<ScanMode>,
+) -> Result<Vec<PortInfo>, String> {
+    commands::scan_port_range(start, end, mode)
+}
+
+/// Get the TCP connections on a local port, in every state but LISTEN
+#[tauri::command]
+fn get_port_connections(port: u16) -> Result<Vec<PortInfo>, String> {
+    commands::get_port_connections(port)
+}
+
+/// Get Unix domain sockets with their process information
+#[tauri::command]
+fn get_unix_sockets(mode: Option<ScanMode>) -> Result<Vec<UnixSocketInfo>, String> {
+    commands::get_unix_sockets(mode)
+}
+
+/// Get detailed information about a process
+#[tauri::command]
+fn get_process_details(pid: u32) -> Result<ProcessDetails, String> {
+    commands::get_process_details(pid)
+}
 
-    ports.sort_by_key(|p| p.port);
-    Ok(ports)
+/// Get a process and its ancestors, nearest first
+#[tauri::command]
+fn get_process_ancestry(pid: u32) -> Result<Vec<ProcessInfo>, String> {
+    commands::get_process_ancestry(pid)
 }
 
 /// Get built-in port presets
 #[tauri::command]
 fn get_presets() -> Vec<PortPreset> {
-    get_builtin_presets()
+    commands::get_presets()
+}
+
+/// Get the most recent kill and signal attempts, newest first
+#[tauri::command]
+fn get_kill_history(
+    kill_log: State<'_, Option<KillLog>>,
+    limit: Option<usize>,
+) -> Vec<KillHistoryEntry> {
+    commands::get_kill_history(kill_log.inner().as_ref(), limit)
+}
+
+/// Start a killed process again from the kill history
+#[tauri::command(async)]
+fn relaunch(
+    kill_log: State<'_, Option<KillLog>>,
+    kill_id: u64,
+    wait_ms: Option<u64>,
+) -> Result<RelaunchResult, String> {
+    commands::relaunch(kill_log.inner().as_ref(), kill_id, wait_ms)
 }
 
 /// Get statistics for the system tray tooltip
 #[tauri::command]
 fn get_tray_stats() -> Result<TrayStats, String> {
-    let ports = get_ports()?;
+    commands::get_tray_stats()
+}
 
-    let tcp_count = ports.iter().filter(|p| p.protocol == Protocol::Tcp).count();
-    let udp_count = ports.iter().filter(|p| p.protocol == Protocol::Udp).count();
+/// Tray tooltip text, e.g. "PortWatcher\n12 ports (8 TCP, 3 UDP, 1 other)\n2 raw/ICMP sockets"
+fn tray_tooltip(stats: &TrayStats) -> String {
+    let other = if stats.other_count > 0 {
+        format!(", {} other", stats.other_count)
+    } else {
+        String::new()
+    };
+    // Raw and ICMP sockets have no port, so they're listed apart
+    let raw = if stats.raw_count > 0 {
+        format!("\n{} raw/ICMP sockets", stats.raw_count)
+    } else {
+        String::new()
+    };
+    format!(
+        "PortWatcher\n{} ports ({} TCP, {} UDP{}){}",
+        stats.total_ports, stats.tcp_count, stats.udp_count, other, raw
+    )
+}
 
-    let unique_pids: HashSet<u32> = ports.iter().map(|p| p.pid).collect();
+/// Tray menu label for a kill history entry, e.g. "node :3000 (PID 4242) - killed"
+fn kill_record_label(record: &KillHistoryEntry) -> String {
+    let name = record.name.as_deref().unwrap_or("unknown");
+    let port = record
+        .port
+        .map(|port| format!(" :{}", port))
+        .unwrap_or_default();
+    let outcome = match &record.outcome {
+        KillResult::Success {
+            stage: KillStage::Sigkill,
+        } => "force-killed".to_string(),
+        KillResult::Success { .. } => "killed".to_string(),
+        KillResult::Signalled { signal } => format!("{} sent", signal),
+        KillResult::PermissionDenied { .. } | KillResult::ElevationRequired { .. } => {
+            "permission denied".to_string()
+        }
+        KillResult::ProcessNotFound { .. } => "already gone".to_string(),
+        KillResult::IdentityMismatch { .. } => "PID reused".to_string(),
+        KillResult::Protected { .. } => "protected".to_string(),
+        KillResult::AuthenticationCancelled => "cancelled".to_string(),
+        KillResult::NotAuthorized => "not authorized".to_string(),
+        KillResult::Error { .. } => "failed".to_string(),
+    };
+    format!("{}{} (PID {}) - {}", name, port, record.pid, outcome)
+}
+
+/// Refill the tray's "Recently Killed" submenu from the kill history
+///
+/// `shown` is the id of the newest record listed; nothing is rebuilt while it
+/// is still the newest.
+fn update_recent_kills<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    submenu: &Submenu<R>,
+    shown: &mut Option<u64>,
+) -> tauri::Result<()> {
+    let kill_log = manager.state::<Option<KillLog>>();
+    let history = commands::get_kill_history(kill_log.inner().as_ref(), Some(RECENT_KILLS));
+    let newest = history.first().map(|record| record.id);
+    let items = submenu.items()?;
+    if newest == *shown && !items.is_empty() {
+        return Ok(());
+    }
+
+    for item in items {
+        submenu.remove(&item)?;
+    }
+    *shown = newest;
 
-    Ok(TrayStats {
-        total_ports: ports.len(),
-        tcp_count,
-        udp_count,
-        process_count: unique_pids.len(),
-    })
+    if history.is_empty() {
+        submenu.append(&MenuItem::new(
+            manager,
+            "No processes killed yet",
+            false,
+            None::<&str>,
+        )?)?;
+    }
+    for record in &history {
+        submenu.append(&MenuItem::new(
+            manager,
+            kill_record_label(record),
+            false,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(())
 }
 
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        // Kill history in the user's state directory, shared by every kill command
+        .manage(KillLog::user())
         .invoke_handler(tauri::generate_handler![
             get_ports,
             kill_process,
             kill_process_elevated,
+            kill_processes,
+            kill_processes_elevated,
+            send_signal,
+            send_signal_elevated,
+            free_port,
+            kill_process_tree,
+            kill_process_group,
             scan_port_range,
+            get_port_connections,
+            get_unix_sockets,
+            get_process_details,
+            get_process_ancestry,
             get_presets,
+            get_kill_history,
+            relaunch,
             get_tray_stats
         ])
         .setup(|app| {
@@ -136,6 +365,18 @@ fn main() {
                 .collect();
             let quick_scan_submenu = Submenu::with_items(app, "Quick Scan", true, &preset_refs)?;
 
+            // Last kills, refreshed whenever a command adds to the history
+            let recent_kills_submenu = Submenu::with_items(app, "Recently Killed", true, &[])?;
+            let mut shown = None;
+            update_recent_kills(app, &recent_kills_submenu, &mut shown)?;
+            let shown = Mutex::new(shown);
+            let app_handle = app.handle().clone();
+            let submenu = recent_kills_submenu.clone();
+            app.listen_any(KILLS_UPDATED, move |_| {
+                let mut shown = shown.lock().unwrap_or_else(|e| e.into_inner());
+                let _ = update_recent_kills(&app_handle, &submenu, &mut shown);
+            });
+
             let separator1 = PredefinedMenuItem::separator(app)?;
             let separator2 = PredefinedMenuItem::separator(app)?;
             let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
@@ -145,6 +386,7 @@ fn main() {
                 &[
                     &refresh_item,
                     &quick_scan_submenu,
+                    &recent_kills_submenu,
                     &separator1,
                     &open_item,
                     &settings_item,
@@ -155,10 +397,7 @@ fn main() {
 
             // Get initial stats for tooltip
             let initial_tooltip = match get_tray_stats() {
-                Ok(stats) => format!(
-                    "PortWatcher\n{} ports ({} TCP, {} UDP)",
-                    stats.total_ports, stats.tcp_count, stats.udp_count
-                ),
+                Ok(stats) => tray_tooltip(&stats),
                 Err(_) => "PortWatcher".to_string(),
             };
 
@@ -237,10 +476,7 @@ fn main() {
             std::thread::spawn(move || loop {
                 std::thread::sleep(Duration::from_secs(10));
                 if let Ok(stats) = get_tray_stats() {
-                    let tooltip = format!(
-                        "PortWatcher\n{} ports ({} TCP, {} UDP)",
-                        stats.total_ports, stats.tcp_count, stats.udp_count
-                    );
+                    let tooltip = tray_tooltip(&stats);
                     let _ = tray_for_thread.set_tooltip(Some(&tooltip));
                 }
             });
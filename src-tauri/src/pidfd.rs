@@ -0,0 +1,81 @@
+use nix::errno::Errno;
+use nix::libc;
+use nix::sys::signal::Signal;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+/// A handle on one specific process that PID reuse can't redirect (Linux 5.3+)
+///
+/// Once opened, signals sent through it reach the process it was opened for or
+/// fail with ESRCH, never a newer process that got the same PID.
+#[derive(Debug)]
+pub struct PidFd {
+    fd: OwnedFd,
+}
+
+impl PidFd {
+    /// Open a pidfd for `pid`
+    ///
+    /// Fails with ESRCH if there is no such process, and ENOSYS on kernels
+    /// without pidfd support.
+    pub fn open(pid: u32) -> Result<Self, Errno> {
+        // SAFETY: pidfd_open takes a PID and flags, and returns a new fd or -1
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        if fd < 0 {
+            return Err(Errno::last());
+        }
+
+        // SAFETY: the kernel just created this fd and nothing else owns it
+        let fd = unsafe { OwnedFd::from_raw_fd(fd as libc::c_int) };
+        Ok(Self { fd })
+    }
+
+    /// Send a signal to the process; ESRCH once it has exited
+    pub fn send_signal(&self, signal: Signal) -> Result<(), Errno> {
+        // SAFETY: a null siginfo means "as if sent by kill()", and flags must be 0
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_pidfd_send_signal,
+                self.fd.as_raw_fd(),
+                signal as libc::c_int,
+                std::ptr::null::<libc::siginfo_t>(),
+                0,
+            )
+        };
+        if ret < 0 {
+            return Err(Errno::last());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_pidfd_outlives_its_process() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pidfd = match PidFd::open(child.id()) {
+            Ok(pidfd) => pidfd,
+            Err(Errno::ENOSYS) => {
+                // Kernel older than 5.3
+                child.kill().unwrap();
+                child.wait().unwrap();
+                return;
+            }
+            Err(e) => panic!("pidfd_open failed: {}", e),
+        };
+
+        pidfd.send_signal(Signal::SIGKILL).unwrap();
+        child.wait().unwrap();
+
+        // Reaped: the pidfd must not reach whatever gets the PID next
+        assert_eq!(pidfd.send_signal(Signal::SIGTERM), Err(Errno::ESRCH));
+    }
+
+    #[test]
+    fn test_pidfd_open_missing_process() {
+        assert!(PidFd::open(999999999).is_err());
+    }
+}
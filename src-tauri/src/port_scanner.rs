@@ -1,68 +1,397 @@
-use crate::types::{Protocol, RawSocketEntry, ScanError};
+use crate::procfs::ProcFs;
+use crate::sock_diag::SockDiag;
+use crate::types::{
+    AddressFamily, PortRange, Protocol, RawSocketEntry, ScanBackend, ScanError, ScanMode,
+    TcpState, UnixSocketEntry, UnixSocketState, UnixSocketType,
+};
+use std::collections::HashMap;
+use std::fmt::Display;
 use std::fs;
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
 
-/// Scanner for reading listening ports from /proc/net/
-pub struct PortScanner;
+/// Socket tables scanned for every request, as (protocol, address family) pairs
+const SOCKET_TABLES: [(Protocol, AddressFamily); 10] = [
+    (Protocol::Tcp, AddressFamily::Ipv4),
+    (Protocol::Tcp, AddressFamily::Ipv6),
+    (Protocol::Udp, AddressFamily::Ipv4),
+    (Protocol::Udp, AddressFamily::Ipv6),
+    (Protocol::UdpLite, AddressFamily::Ipv4),
+    (Protocol::UdpLite, AddressFamily::Ipv6),
+    (Protocol::Raw, AddressFamily::Ipv4),
+    (Protocol::Raw, AddressFamily::Ipv6),
+    (Protocol::Icmp, AddressFamily::Ipv4),
+    (Protocol::Icmp, AddressFamily::Ipv6),
+];
+
+/// Flag set in /proc/net/unix for sockets accepting connections (__SO_ACCEPTCON)
+const UNIX_ACCEPTCON: u32 = 0x0001_0000;
+
+/// Scanner for reading listening ports from netlink sock_diag or /proc/net/
+pub struct PortScanner {
+    /// procfs tree the socket tables and namespaces are read from
+    procfs: ProcFs,
+    /// PIDs grouped by network namespace, when a caller already knows them
+    namespaces: Option<HashMap<u64, Vec<u32>>>,
+}
 
 impl PortScanner {
-    /// Scan all listening TCP and UDP ports
-    pub fn scan_all() -> Result<Vec<RawSocketEntry>, ScanError> {
-        let mut entries = Vec::new();
+    /// Create a scanner reading the system's /proc
+    pub fn new() -> Self {
+        Self::with_procfs(ProcFs::default())
+    }
 
-        // Scan TCP ports
-        entries.extend(Self::parse_tcp()?);
+    /// Create a scanner reading the given procfs tree
+    pub fn with_procfs(procfs: ProcFs) -> Self {
+        Self {
+            procfs,
+            namespaces: None,
+        }
+    }
 
-        // Scan UDP ports (UDP doesn't have LISTEN state, include all bound ports)
-        entries.extend(Self::parse_udp()?);
+    /// Use these PIDs per network namespace instead of reading every process's
+    ///
+    /// Lets a long-lived `ProcessResolver` spare each scan a readlink per process
+    /// (see `ProcessResolver::pids_by_netns`).
+    pub fn with_namespaces(mut self, namespaces: HashMap<u64, Vec<u32>>) -> Self {
+        self.namespaces = Some(namespaces);
+        self
+    }
 
-        Ok(entries)
+    /// Scan all TCP, UDP, UDP-Lite, raw, ICMP and SCTP sockets matching the scan mode
+    pub fn scan_all(&self, mode: ScanMode) -> Result<Vec<RawSocketEntry>, ScanError> {
+        self.scan_with(ScanBackend::Auto, mode, None)
     }
 
     /// Scan a specific port range (inclusive)
-    pub fn scan_range(start: u16, end: u16) -> Result<Vec<RawSocketEntry>, ScanError> {
-        let all_entries = Self::scan_all()?;
+    pub fn scan_range(
+        &self,
+        start: u16,
+        end: u16,
+        mode: ScanMode,
+    ) -> Result<Vec<RawSocketEntry>, ScanError> {
+        self.scan_with(ScanBackend::Auto, mode, Some(&PortRange { start, end }))
+    }
 
-        // Filter to only include ports within the specified range
-        let filtered: Vec<RawSocketEntry> = all_entries
+    /// Scan the TCP sockets on a local port in every state but LISTEN
+    ///
+    /// Covers live connections as well as those being set up or torn down
+    /// (SYN_RECV, CLOSE_WAIT, TIME_WAIT, ...).
+    pub fn scan_connections(&self, port: u16) -> Result<Vec<RawSocketEntry>, ScanError> {
+        let entries = self.scan_range(port, port, ScanMode::All)?;
+        Ok(entries
             .into_iter()
-            .filter(|entry| entry.local_port >= start && entry.local_port <= end)
-            .collect();
+            .filter(|entry| {
+                entry.protocol == Protocol::Tcp && entry.state != Some(TcpState::Listen)
+            })
+            .collect())
+    }
+
+    /// Scan every socket table with an explicit backend
+    ///
+    /// With `ScanBackend::Auto`, each table is requested over netlink first and
+    /// re-read from /proc if netlink fails (e.g. udp_diag isn't loaded).
+    ///
+    /// Netlink only sees the caller's network namespace, so sockets in other
+    /// namespaces (containers, `ip netns`) are always read from /proc/<pid>/net/.
+    /// They are skipped if our own namespace can't be identified.
+    /// It also can't see a foreign procfs tree, so `Auto` only uses it when
+    /// scanning the system's own /proc.
+    pub fn scan_with(
+        &self,
+        backend: ScanBackend,
+        mode: ScanMode,
+        range: Option<&PortRange>,
+    ) -> Result<Vec<RawSocketEntry>, ScanError> {
+        let net_dir = self.procfs.net_dir();
+        let backend = match backend {
+            ScanBackend::Auto if !self.procfs.is_system() => ScanBackend::Proc,
+            backend => backend,
+        };
+
+        let mut entries = Vec::new();
+
+        for (protocol, family) in SOCKET_TABLES {
+            let table = match backend {
+                ScanBackend::Proc => {
+                    Self::scan_proc_table(&net_dir, protocol, family, mode, range)?
+                }
+                _ if !SockDiag::supports(protocol) => {
+                    Self::scan_proc_table(&net_dir, protocol, family, mode, range)?
+                }
+                ScanBackend::Netlink => SockDiag::query(protocol, family, mode, range)?,
+                ScanBackend::Auto => match SockDiag::query(protocol, family, mode, range) {
+                    Ok(table) => table,
+                    Err(_) => Self::scan_proc_table(&net_dir, protocol, family, mode, range)?,
+                },
+            };
+            entries.extend(table);
+        }
+
+        // SCTP is only exposed through /proc/net/sctp/
+        entries.extend(Self::scan_sctp(&net_dir, mode, range)?);
+
+        let own_netns = self.netns_of("self");
+        for entry in &mut entries {
+            entry.netns = own_netns;
+        }
+
+        // Without our own namespace, any of the others could be the one just read
+        if let Some(own_netns) = own_netns {
+            entries.extend(self.scan_other_namespaces(own_netns, mode, range)?);
+        }
+
+        Ok(entries)
+    }
+
+    /// Scan the socket tables of every network namespace other than our own
+    ///
+    /// Tables are read once per namespace, through the /proc/<pid>/net/ view of
+    /// any process inside it. Namespaces whose processes we can't inspect are
+    /// skipped, since their sockets couldn't be attributed anyway.
+    fn scan_other_namespaces(
+        &self,
+        own_netns: u64,
+        mode: ScanMode,
+        range: Option<&PortRange>,
+    ) -> Result<Vec<RawSocketEntry>, ScanError> {
+        let mut entries = Vec::new();
+
+        let groups = match &self.namespaces {
+            Some(groups) => groups.clone(),
+            None => self.group_pids_by_netns()?,
+        };
+
+        for (netns, pids) in groups {
+            if netns == own_netns {
+                continue;
+            }
 
-        Ok(filtered)
+            // Any member works; try the next one if a process exits mid-scan
+            for pid in pids {
+                let net_dir = self.procfs.pid_path(pid, "net");
+                if let Ok(mut ns_entries) = Self::scan_proc_dir(&net_dir, mode, range) {
+                    for entry in &mut ns_entries {
+                        entry.netns = Some(netns);
+                    }
+                    entries.extend(ns_entries);
+                    break;
+                }
+            }
+        }
+
+        Ok(entries)
     }
 
-    /// Parse /proc/net/tcp for listening TCP sockets
-    fn parse_tcp() -> Result<Vec<RawSocketEntry>, ScanError> {
-        let content = fs::read_to_string("/proc/net/tcp").map_err(|e| ScanError::IoError {
-            path: "/proc/net/tcp".to_string(),
+    /// Read every socket table under a net directory (/proc/net or /proc/<pid>/net)
+    fn scan_proc_dir(
+        net_dir: &Path,
+        mode: ScanMode,
+        range: Option<&PortRange>,
+    ) -> Result<Vec<RawSocketEntry>, ScanError> {
+        let mut entries = Vec::new();
+
+        for (protocol, family) in SOCKET_TABLES {
+            entries.extend(Self::scan_proc_table(net_dir, protocol, family, mode, range)?);
+        }
+        entries.extend(Self::scan_sctp(net_dir, mode, range)?);
+
+        Ok(entries)
+    }
+
+    /// Group PIDs by the inode of their network namespace
+    fn group_pids_by_netns(&self) -> Result<HashMap<u64, Vec<u32>>, ScanError> {
+        let mut groups: HashMap<u64, Vec<u32>> = HashMap::new();
+
+        let root = self.procfs.root();
+        let proc_dir = fs::read_dir(root).map_err(|e| ScanError::IoError {
+            path: root.display().to_string(),
             source: e,
         })?;
 
-        Self::parse_proc_net(&content, Protocol::Tcp, true)
+        for entry in proc_dir.flatten() {
+            let name = entry.file_name();
+            let Some(pid) = name.to_str().and_then(|n| n.parse::<u32>().ok()) else {
+                continue;
+            };
+
+            // Unreadable for other users' processes unless we're root
+            if let Some(netns) = self.netns_of(pid) {
+                groups.entry(netns).or_default().push(pid);
+            }
+        }
+
+        Ok(groups)
+    }
+
+    /// Network namespace inode of a process ("self" or a PID)
+    fn netns_of(&self, pid: impl Display) -> Option<u64> {
+        Self::read_netns(&self.procfs, pid)
+    }
+
+    /// Network namespace inode of a process under a procfs tree
+    ///
+    /// /proc/<pid>/ns/net is a symlink to "net:[4026531840]".
+    pub(crate) fn read_netns(procfs: &ProcFs, pid: impl Display) -> Option<u64> {
+        let target = fs::read_link(procfs.pid_path(pid, "ns/net")).ok()?;
+        Self::parse_netns_link(&target.to_string_lossy())
+    }
+
+    /// Parse a namespace link target like "net:[4026531840]"
+    fn parse_netns_link(target: &str) -> Option<u64> {
+        target
+            .strip_prefix("net:[")
+            .and_then(|s| s.strip_suffix(']'))
+            .and_then(|s| s.parse().ok())
     }
 
-    /// Parse /proc/net/udp for UDP sockets
-    fn parse_udp() -> Result<Vec<RawSocketEntry>, ScanError> {
-        let content = fs::read_to_string("/proc/net/udp").map_err(|e| ScanError::IoError {
-            path: "/proc/net/udp".to_string(),
+    /// Scan SCTP endpoints (and associations, in `ScanMode::All`)
+    ///
+    /// Returns nothing when the sctp module isn't loaded, since /proc/net/sctp/
+    /// only exists once it is.
+    fn scan_sctp(
+        net_dir: &Path,
+        mode: ScanMode,
+        range: Option<&PortRange>,
+    ) -> Result<Vec<RawSocketEntry>, ScanError> {
+        let mut entries = match Self::read_optional(&net_dir.join("sctp/eps"))? {
+            Some(content) => Self::parse_sctp_eps(&content)?,
+            None => return Ok(Vec::new()),
+        };
+
+        if mode == ScanMode::All {
+            if let Some(content) = Self::read_optional(&net_dir.join("sctp/assocs"))? {
+                entries.extend(Self::parse_sctp_assocs(&content)?);
+            }
+        }
+
+        // Filter to only include ports within the specified range
+        if let Some(range) = range {
+            entries.retain(|e| e.local_port >= range.start && e.local_port <= range.end);
+        }
+
+        Ok(entries)
+    }
+
+    /// Scan Unix domain sockets from /proc/net/unix
+    ///
+    /// In listening mode, only sockets accepting connections and bound datagram
+    /// sockets are returned, mirroring the TCP/UDP behavior.
+    pub fn scan_unix(&self, mode: ScanMode) -> Result<Vec<UnixSocketEntry>, ScanError> {
+        let path = self.procfs.net_dir().join("unix");
+        let content = fs::read_to_string(&path).map_err(|e| ScanError::IoError {
+            path: path.display().to_string(),
             source: e,
         })?;
 
-        // UDP doesn't have LISTEN state, so we don't filter by state
-        Self::parse_proc_net(&content, Protocol::Udp, false)
+        let mut entries = Vec::new();
+
+        for line in content.lines().skip(1) {
+            // Skip header line
+            if let Some(entry) = Self::parse_unix_line(line)? {
+                let bound = entry.path.is_some() || entry.abstract_name.is_some();
+                let is_server = entry.listening
+                    || (entry.socket_type == UnixSocketType::Dgram
+                        && entry.state == UnixSocketState::Unconnected
+                        && bound);
+
+                if mode == ScanMode::All || is_server {
+                    entries.push(entry);
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Read one socket table from a net directory, filtering by mode and port range
+    fn scan_proc_table(
+        net_dir: &Path,
+        protocol: Protocol,
+        family: AddressFamily,
+        mode: ScanMode,
+        range: Option<&PortRange>,
+    ) -> Result<Vec<RawSocketEntry>, ScanError> {
+        let Some(name) = Self::proc_table_name(protocol, family) else {
+            return Ok(Vec::new());
+        };
+        let path = net_dir.join(name);
+
+        // The IPv4 TCP/UDP tables always exist; the others depend on kernel config
+        let required =
+            family == AddressFamily::Ipv4 && matches!(protocol, Protocol::Tcp | Protocol::Udp);
+        let content = if required {
+            fs::read_to_string(&path).map_err(|e| ScanError::IoError {
+                path: path.display().to_string(),
+                source: e,
+            })?
+        } else {
+            match Self::read_optional(&path)? {
+                Some(content) => content,
+                None => return Ok(Vec::new()),
+            }
+        };
+
+        let mut entries = Self::parse_proc_net(&content, protocol, family, mode)?;
+
+        // Filter to only include ports within the specified range
+        if let Some(range) = range {
+            entries.retain(|e| e.local_port >= range.start && e.local_port <= range.end);
+        }
+
+        Ok(entries)
+    }
+
+    /// File name of the net directory table for a protocol and address family
+    ///
+    /// None for SCTP, whose endpoint and association tables cover both families
+    /// and are read by scan_sctp.
+    fn proc_table_name(protocol: Protocol, family: AddressFamily) -> Option<&'static str> {
+        let name = match (protocol, family) {
+            (Protocol::Tcp, AddressFamily::Ipv4) => "tcp",
+            (Protocol::Tcp, AddressFamily::Ipv6) => "tcp6",
+            (Protocol::Udp, AddressFamily::Ipv4) => "udp",
+            (Protocol::Udp, AddressFamily::Ipv6) => "udp6",
+            (Protocol::UdpLite, AddressFamily::Ipv4) => "udplite",
+            (Protocol::UdpLite, AddressFamily::Ipv6) => "udplite6",
+            (Protocol::Raw, AddressFamily::Ipv4) => "raw",
+            (Protocol::Raw, AddressFamily::Ipv6) => "raw6",
+            (Protocol::Icmp, AddressFamily::Ipv4) => "icmp",
+            (Protocol::Icmp, AddressFamily::Ipv6) => "icmp6",
+            (Protocol::Sctp, _) => return None,
+        };
+        Some(name)
+    }
+
+    /// Read a /proc/net/ file that may legitimately be absent
+    ///
+    /// The IPv6 tables don't exist when the kernel is booted with `ipv6.disable=1`,
+    /// and UDP-Lite or ping sockets can be compiled out, which is not an error for
+    /// our purposes.
+    fn read_optional(path: &Path) -> Result<Option<String>, ScanError> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Some(content)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ScanError::IoError {
+                path: path.display().to_string(),
+                source: e,
+            }),
+        }
     }
 
     /// Parse the content of a /proc/net/ file
     fn parse_proc_net(
         content: &str,
         protocol: Protocol,
-        filter_listen: bool,
+        family: AddressFamily,
+        mode: ScanMode,
     ) -> Result<Vec<RawSocketEntry>, ScanError> {
         let mut entries = Vec::new();
 
         for line in content.lines().skip(1) {
             // Skip header line
-            if let Some(entry) = Self::parse_line(line, protocol, filter_listen)? {
+            if let Some(entry) = Self::parse_line(line, protocol, family, mode)? {
                 entries.push(entry);
             }
         }
@@ -70,15 +399,20 @@ impl PortScanner {
         Ok(entries)
     }
 
-    /// Parse a single line from /proc/net/tcp or /proc/net/udp
+    /// Parse a single line from /proc/net/tcp, /proc/net/udp, or another inet table
     ///
     /// Format:
     /// sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
     /// 0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 12345
-    fn parse_line(
+    ///
+    /// The udplite, raw and icmp tables share this layout, and the IPv6 tables use
+    /// it with 32 hex chars per address. For raw sockets the port column holds
+    /// the IP protocol number; for ICMP sockets it holds the echo identifier.
+    pub(crate) fn parse_line(
         line: &str,
         protocol: Protocol,
-        filter_listen: bool,
+        family: AddressFamily,
+        mode: ScanMode,
     ) -> Result<Option<RawSocketEntry>, ScanError> {
         let parts: Vec<&str> = line.split_whitespace().collect();
 
@@ -87,11 +421,19 @@ impl PortScanner {
         }
 
         // Parse state (column 4, 0-indexed at 3)
-        let state = parts[3];
+        // Other protocols reuse the TCP state codes internally, but they carry no
+        // meaning we report
+        let state = match protocol {
+            Protocol::Tcp => match Self::parse_state(parts[3])? {
+                Some(state) => Some(state),
+                None => return Ok(None), // Unknown state code
+            },
+            _ => None,
+        };
 
-        // For TCP, only include LISTEN state (0x0A = 10)
-        // For UDP, include all bound sockets
-        if filter_listen && state != "0A" {
+        // In listening mode, only include TCP sockets in LISTEN state
+        // For connectionless protocols, include all bound sockets
+        if mode == ScanMode::Listening && state.is_some_and(|s| s != TcpState::Listen) {
             return Ok(None);
         }
 
@@ -104,6 +446,17 @@ impl PortScanner {
             return Ok(None);
         }
 
+        // Parse remote address:port (column 3, 0-indexed at 2)
+        let (remote_address, remote_port) = Self::parse_address_port(parts[2])?;
+
+        // Parse tx_queue:rx_queue (column 5, 0-indexed at 4)
+        let (tx_queue, rx_queue) = Self::parse_queues(parts[4])?;
+
+        // Parse uid (column 8, 0-indexed at 7)
+        let uid: u32 = parts[7].parse().map_err(|_| {
+            ScanError::ParseError(format!("Invalid uid: {}", parts[7]))
+        })?;
+
         // Parse inode (column 10, 0-indexed at 9)
         let inode: u64 = parts[9].parse().map_err(|_| {
             ScanError::ParseError(format!("Invalid inode: {}", parts[9]))
@@ -117,11 +470,40 @@ impl PortScanner {
         Ok(Some(RawSocketEntry {
             local_address: address,
             local_port: port,
+            remote_address,
+            remote_port,
             inode,
             protocol,
+            family,
+            state,
+            tx_queue,
+            rx_queue,
+            uid,
+            netns: None,
         }))
     }
 
+    /// Parse a hex "tx_queue:rx_queue" pair (e.g., "00000000:00000003")
+    fn parse_queues(field: &str) -> Result<(u32, u32), ScanError> {
+        let (tx, rx) = field
+            .split_once(':')
+            .ok_or_else(|| ScanError::ParseError(format!("Invalid queue field: {}", field)))?;
+
+        let parse = |hex: &str| {
+            u32::from_str_radix(hex, 16)
+                .map_err(|_| ScanError::ParseError(format!("Invalid queue hex: {}", hex)))
+        };
+
+        Ok((parse(tx)?, parse(rx)?))
+    }
+
+    /// Parse a hex TCP state code (e.g., "0A")
+    fn parse_state(hex: &str) -> Result<Option<TcpState>, ScanError> {
+        let code = u8::from_str_radix(hex, 16)
+            .map_err(|_| ScanError::ParseError(format!("Invalid state hex: {}", hex)))?;
+        Ok(TcpState::from_code(code))
+    }
+
     /// Parse hex address:port format (e.g., "0100007F:1F90")
     fn parse_address_port(addr_port: &str) -> Result<(String, u16), ScanError> {
         let parts: Vec<&str> = addr_port.split(':').collect();
@@ -138,24 +520,278 @@ impl PortScanner {
         Ok((address, port))
     }
 
-    /// Convert hex IP address to dotted notation
-    /// Note: /proc/net stores IP in little-endian format
+    /// Parse a single line from /proc/net/unix
+    ///
+    /// Format:
+    /// Num       RefCount Protocol Flags    Type St Inode Path
+    /// 0000000000000000: 00000002 00000000 00010000 0001 01 12345 /run/docker.sock
+    ///
+    /// The path is absent for unnamed sockets and starts with '@' for abstract ones.
+    fn parse_unix_line(line: &str) -> Result<Option<UnixSocketEntry>, ScanError> {
+        // Split off the 7 fixed columns; whatever remains is the path, which may contain spaces
+        let mut rest = line.trim_start();
+        let mut parts = Vec::with_capacity(7);
+        while parts.len() < 7 && !rest.is_empty() {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            parts.push(&rest[..end]);
+            rest = rest[end..].trim_start();
+        }
+
+        if parts.len() < 7 {
+            return Ok(None); // Skip malformed lines
+        }
+
+        // Parse flags (column 4, 0-indexed at 3); __SO_ACCEPTCON marks listening sockets
+        let flags = u32::from_str_radix(parts[3], 16)
+            .map_err(|_| ScanError::ParseError(format!("Invalid flags: {}", parts[3])))?;
+        let listening = flags & UNIX_ACCEPTCON != 0;
+
+        // Parse type (column 5, 0-indexed at 4)
+        let socket_type = match parts[4] {
+            "0001" => UnixSocketType::Stream,
+            "0002" => UnixSocketType::Dgram,
+            "0005" => UnixSocketType::SeqPacket,
+            _ => return Ok(None), // Unknown socket type
+        };
+
+        // Parse state (column 6, 0-indexed at 5)
+        let state = match parts[5] {
+            "01" => UnixSocketState::Unconnected,
+            "02" => UnixSocketState::Connecting,
+            "03" => UnixSocketState::Connected,
+            "04" => UnixSocketState::Disconnecting,
+            _ => return Ok(None), // Unknown state
+        };
+
+        // Parse inode (column 7, 0-indexed at 6)
+        let inode: u64 = parts[6]
+            .parse()
+            .map_err(|_| ScanError::ParseError(format!("Invalid inode: {}", parts[6])))?;
+
+        if inode == 0 {
+            return Ok(None);
+        }
+
+        // Parse path (column 8), distinguishing abstract names
+        let (path, abstract_name) = match rest.trim_end() {
+            "" => (None, None),
+            name if name.starts_with('@') => (None, Some(name[1..].to_string())),
+            path => (Some(path.to_string()), None),
+        };
+
+        Ok(Some(UnixSocketEntry {
+            path,
+            abstract_name,
+            socket_type,
+            state,
+            listening,
+            inode,
+        }))
+    }
+
+    /// Parse the content of /proc/net/sctp/eps
+    ///
+    /// Format:
+    ///  ENDPT     SOCK   STY SST HBKT LPORT   UID INODE LADDRS
+    /// ffff88017e0a0200 ffff880299f7fa00 2   10  29   11165   200 299689357 10.0.0.1 10.0.0.2
+    ///
+    /// A multi-homed endpoint lists every bound address; the first one is reported.
+    fn parse_sctp_eps(content: &str) -> Result<Vec<RawSocketEntry>, ScanError> {
+        let mut entries = Vec::new();
+
+        for line in content.lines().skip(1) {
+            // Skip header line
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 9 {
+                continue; // Skip malformed lines
+            }
+
+            // SST is the socket state, which SCTP encodes with the TCP state values
+            let state = TcpState::from_code(Self::parse_sctp_number(parts[3])? as u8);
+            let local_port = Self::parse_sctp_number(parts[5])? as u16;
+            let uid = Self::parse_sctp_number(parts[6])? as u32;
+            let inode = Self::parse_sctp_number(parts[7])?;
+            let (local_ip, _) = Self::parse_sctp_addresses(&parts[8..]);
+
+            let Some(local_ip) = local_ip else {
+                continue;
+            };
+            if local_port == 0 || inode == 0 {
+                continue;
+            }
+
+            entries.push(RawSocketEntry {
+                local_address: local_ip.to_string(),
+                local_port,
+                remote_address: Self::unspecified_address(&local_ip),
+                remote_port: 0,
+                inode,
+                protocol: Protocol::Sctp,
+                family: Self::address_family(&local_ip),
+                state,
+                // Endpoints don't report queue depths
+                tx_queue: 0,
+                rx_queue: 0,
+                uid,
+                netns: None,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Parse the content of /proc/net/sctp/assocs
+    ///
+    /// Format:
+    ///  ASSOC     SOCK   STY SST ST HBKT ASSOC-ID TX_QUEUE RX_QUEUE UID INODE LPORT RPORT LADDRS <-> RADDRS HBINT INS OUTS ...
+    /// ffff8801 ffff8802 2   1   3  0    2        0        0     1000 123456 3868  40000 10.0.0.1 <-> *10.0.0.2 7500 10 10 ...
+    ///
+    /// The primary peer address is marked with '*' and preferred when present.
+    fn parse_sctp_assocs(content: &str) -> Result<Vec<RawSocketEntry>, ScanError> {
+        let mut entries = Vec::new();
+
+        for line in content.lines().skip(1) {
+            // Skip header line
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 16 {
+                continue; // Skip malformed lines
+            }
+
+            let state = Self::sctp_assoc_state(Self::parse_sctp_number(parts[4])?);
+            let tx_queue = Self::parse_sctp_number(parts[7])? as u32;
+            let rx_queue = Self::parse_sctp_number(parts[8])? as u32;
+            let uid = Self::parse_sctp_number(parts[9])? as u32;
+            let inode = Self::parse_sctp_number(parts[10])?;
+            let local_port = Self::parse_sctp_number(parts[11])? as u16;
+            let remote_port = Self::parse_sctp_number(parts[12])? as u16;
+
+            let (local_ip, consumed) = Self::parse_sctp_addresses(&parts[13..]);
+            let remote_start = 13 + consumed + 1; // Skip the "<->" separator
+            if parts.get(remote_start - 1) != Some(&"<->") {
+                continue;
+            }
+            let (remote_ip, _) = Self::parse_sctp_addresses(&parts[remote_start..]);
+
+            let (Some(local_ip), Some(remote_ip)) = (local_ip, remote_ip) else {
+                continue;
+            };
+            if local_port == 0 || inode == 0 {
+                continue;
+            }
+
+            entries.push(RawSocketEntry {
+                local_address: local_ip.to_string(),
+                local_port,
+                remote_address: remote_ip.to_string(),
+                remote_port,
+                inode,
+                protocol: Protocol::Sctp,
+                family: Self::address_family(&local_ip),
+                state,
+                tx_queue,
+                rx_queue,
+                uid,
+                netns: None,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Parse a run of SCTP address tokens
+    ///
+    /// Returns the primary address ('*'-prefixed) or else the first one, along with
+    /// the number of tokens consumed.
+    fn parse_sctp_addresses(tokens: &[&str]) -> (Option<IpAddr>, usize) {
+        let mut first = None;
+        let mut primary = None;
+        let mut consumed = 0;
+
+        for token in tokens {
+            let (is_primary, addr) = match token.strip_prefix('*') {
+                Some(addr) => (true, addr),
+                None => (false, *token),
+            };
+            let Ok(ip) = addr.parse::<IpAddr>() else {
+                break;
+            };
+
+            first.get_or_insert(ip);
+            if is_primary {
+                primary.get_or_insert(ip);
+            }
+            consumed += 1;
+        }
+
+        (primary.or(first), consumed)
+    }
+
+    /// Map an SCTP association state (SCTP_STATE_*) onto the closest TCP state
+    fn sctp_assoc_state(code: u64) -> Option<TcpState> {
+        match code {
+            0 => Some(TcpState::Close),
+            1 | 2 => Some(TcpState::SynSent), // COOKIE_WAIT, COOKIE_ECHOED
+            3 => Some(TcpState::Established),
+            4..=7 => Some(TcpState::Closing), // SHUTDOWN_*
+            _ => None,
+        }
+    }
+
+    /// Parse a decimal column from the SCTP tables
+    fn parse_sctp_number(field: &str) -> Result<u64, ScanError> {
+        field
+            .parse()
+            .map_err(|_| ScanError::ParseError(format!("Invalid SCTP field: {}", field)))
+    }
+
+    /// The wildcard address of the same family as `ip`
+    fn unspecified_address(ip: &IpAddr) -> String {
+        match ip {
+            IpAddr::V4(_) => Ipv4Addr::UNSPECIFIED.to_string(),
+            IpAddr::V6(_) => Ipv6Addr::UNSPECIFIED.to_string(),
+        }
+    }
+
+    /// The address family of `ip`
+    fn address_family(ip: &IpAddr) -> AddressFamily {
+        match ip {
+            IpAddr::V4(_) => AddressFamily::Ipv4,
+            IpAddr::V6(_) => AddressFamily::Ipv6,
+        }
+    }
+
+    /// Convert hex IP address to its textual form
+    ///
+    /// IPv4 addresses are 8 hex chars, IPv6 addresses are 32 hex chars.
+    /// Note: /proc/net stores each 32-bit word of the address in little-endian format
     fn hex_to_ip(hex: &str) -> Result<String, ScanError> {
-        if hex.len() != 8 {
+        if hex.len() != 8 && hex.len() != 32 {
             return Err(ScanError::ParseError(format!(
                 "Invalid IP hex length: {}",
                 hex
             )));
         }
 
-        let bytes: Result<Vec<u8>, _> = (0..4)
+        let bytes: Result<Vec<u8>, _> = (0..hex.len() / 2)
             .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16))
             .collect();
 
-        let bytes = bytes.map_err(|_| ScanError::ParseError(format!("Invalid IP hex: {}", hex)))?;
+        let mut bytes =
+            bytes.map_err(|_| ScanError::ParseError(format!("Invalid IP hex: {}", hex)))?;
 
-        // Reverse for little-endian to big-endian conversion
-        Ok(format!("{}.{}.{}.{}", bytes[3], bytes[2], bytes[1], bytes[0]))
+        // Reverse each 32-bit word for little-endian to big-endian conversion
+        for word in bytes.chunks_mut(4) {
+            word.reverse();
+        }
+
+        if bytes.len() == 4 {
+            Ok(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).to_string())
+        } else {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(&bytes);
+            // IPv4-mapped addresses are rendered as ::ffff:a.b.c.d
+            Ok(Ipv6Addr::from(octets).to_string())
+        }
     }
 
     /// Convert hex port to u16
@@ -165,9 +801,16 @@ impl PortScanner {
     }
 }
 
+impl Default for PortScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::net::{TcpListener, UdpSocket};
 
     #[test]
     fn test_hex_to_ip_localhost() {
@@ -204,35 +847,400 @@ mod tests {
     #[test]
     fn test_parse_tcp_line_listen() {
         let line = "   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 12345 1 0000000000000000 100 0 0 10 0";
-        let entry = PortScanner::parse_line(line, Protocol::Tcp, true)
-            .unwrap()
-            .unwrap();
+        let entry = PortScanner::parse_line(
+            line,
+            Protocol::Tcp,
+            AddressFamily::Ipv4,
+            ScanMode::Listening,
+        )
+        .unwrap()
+        .unwrap();
 
         assert_eq!(entry.local_address, "127.0.0.1");
         assert_eq!(entry.local_port, 8080);
+        assert_eq!(entry.remote_address, "0.0.0.0");
+        assert_eq!(entry.remote_port, 0);
         assert_eq!(entry.inode, 12345);
         assert_eq!(entry.protocol, Protocol::Tcp);
+        assert_eq!(entry.family, AddressFamily::Ipv4);
+        assert_eq!(entry.state, Some(TcpState::Listen));
+        assert_eq!(entry.tx_queue, 0);
+        assert_eq!(entry.rx_queue, 0);
+        assert_eq!(entry.uid, 1000);
+    }
+
+    #[test]
+    fn test_parse_tcp_line_listen_with_backlog() {
+        // 5 connections waiting to be accepted
+        let line = "   0: 00000000:0BB8 00000000:0000 0A 00000000:00000005 00:00000000 00000000  1000        0 12345 1 0000000000000000 100 0 0 10 0";
+        let entry = PortScanner::parse_line(
+            line,
+            Protocol::Tcp,
+            AddressFamily::Ipv4,
+            ScanMode::Listening,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(entry.local_port, 3000);
+        assert_eq!(entry.tx_queue, 0);
+        assert_eq!(entry.rx_queue, 5);
     }
 
     #[test]
     fn test_parse_tcp_line_non_listen() {
         // State 01 = ESTABLISHED, should be filtered out
         let line = "   0: 0100007F:1F90 00000000:0000 01 00000000:00000000 00:00000000 00000000  1000        0 12345";
-        let entry = PortScanner::parse_line(line, Protocol::Tcp, true).unwrap();
+        let entry = PortScanner::parse_line(
+            line,
+            Protocol::Tcp,
+            AddressFamily::Ipv4,
+            ScanMode::Listening,
+        )
+        .unwrap();
         assert!(entry.is_none());
     }
 
+    #[test]
+    fn test_parse_tcp_line_established_all_mode() {
+        // State 01 = ESTABLISHED, included when scanning every state
+        let line = "   0: 0100007F:1F90 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 12345";
+        let entry = PortScanner::parse_line(
+            line,
+            Protocol::Tcp,
+            AddressFamily::Ipv4,
+            ScanMode::All,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(entry.local_port, 8080);
+        assert_eq!(entry.remote_address, "127.0.0.1");
+        assert_eq!(entry.remote_port, 54321);
+        assert_eq!(entry.state, Some(TcpState::Established));
+    }
+
+    #[test]
+    fn test_parse_tcp_line_close_wait_all_mode() {
+        let line = "   0: 0100007F:1F90 0100007F:D431 08 00000000:00000000 00:00000000 00000000  1000        0 12345";
+        let entry = PortScanner::parse_line(
+            line,
+            Protocol::Tcp,
+            AddressFamily::Ipv4,
+            ScanMode::All,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(entry.state, Some(TcpState::CloseWait));
+    }
+
     #[test]
     fn test_parse_udp_line() {
         // UDP uses state 07 for bound sockets
         let line = "   0: 00000000:0035 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 11111 1 0000000000000000 0";
-        let entry = PortScanner::parse_line(line, Protocol::Udp, false)
-            .unwrap()
-            .unwrap();
+        let entry = PortScanner::parse_line(
+            line,
+            Protocol::Udp,
+            AddressFamily::Ipv4,
+            ScanMode::Listening,
+        )
+        .unwrap()
+        .unwrap();
 
         assert_eq!(entry.local_address, "0.0.0.0");
         assert_eq!(entry.local_port, 53); // DNS port
         assert_eq!(entry.inode, 11111);
         assert_eq!(entry.protocol, Protocol::Udp);
+        assert_eq!(entry.state, None);
+    }
+
+    #[test]
+    fn test_hex_to_ip_v6_any() {
+        let ip = PortScanner::hex_to_ip("00000000000000000000000000000000").unwrap();
+        assert_eq!(ip, "::");
+    }
+
+    #[test]
+    fn test_hex_to_ip_v6_loopback() {
+        // ::1 with each 32-bit word in little-endian
+        let ip = PortScanner::hex_to_ip("00000000000000000000000001000000").unwrap();
+        assert_eq!(ip, "::1");
+    }
+
+    #[test]
+    fn test_hex_to_ip_v4_mapped() {
+        // ::ffff:127.0.0.1
+        let ip = PortScanner::hex_to_ip("0000000000000000FFFF00000100007F").unwrap();
+        assert_eq!(ip, "::ffff:127.0.0.1");
+    }
+
+    #[test]
+    fn test_hex_to_ip_invalid_length() {
+        assert!(PortScanner::hex_to_ip("0100007").is_err());
+        assert!(PortScanner::hex_to_ip("0000000000000000000000000000000").is_err());
+    }
+
+    #[test]
+    fn test_parse_tcp6_line_listen() {
+        let line = "   0: 00000000000000000000000000000000:0BB8 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 54321 1 0000000000000000 100 0 0 10 0";
+        let entry = PortScanner::parse_line(
+            line,
+            Protocol::Tcp,
+            AddressFamily::Ipv6,
+            ScanMode::Listening,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(entry.local_address, "::");
+        assert_eq!(entry.local_port, 3000);
+        assert_eq!(entry.remote_address, "::");
+        assert_eq!(entry.remote_port, 0);
+        assert_eq!(entry.inode, 54321);
+        assert_eq!(entry.family, AddressFamily::Ipv6);
+    }
+
+    #[test]
+    fn test_proc_scan_sees_own_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let range = PortRange {
+            start: port,
+            end: port,
+        };
+        let entries = PortScanner::new()
+            .scan_with(ScanBackend::Proc, ScanMode::Listening, Some(&range))
+            .unwrap();
+
+        assert!(entries
+            .iter()
+            .any(|e| e.protocol == Protocol::Tcp && e.local_port == port));
+        assert!(entries.iter().all(|e| e.local_port == port));
+    }
+
+    #[test]
+    #[ignore = "needs NETLINK_SOCK_DIAG, which sandboxed builds may refuse; run with --ignored"]
+    fn test_netlink_and_proc_backends_agree() {
+        // Bind a few sockets of our own so both backends have known entries to report
+        let tcp4 = TcpListener::bind("127.0.0.1:0").unwrap();
+        let tcp6 = TcpListener::bind("[::1]:0").ok();
+        let udp4 = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        let mut ports = vec![
+            tcp4.local_addr().unwrap().port(),
+            udp4.local_addr().unwrap().port(),
+        ];
+        if let Some(listener) = &tcp6 {
+            ports.push(listener.local_addr().unwrap().port());
+        }
+
+        for port in ports {
+            let range = PortRange {
+                start: port,
+                end: port,
+            };
+
+            let scanner = PortScanner::new();
+            let scan = |backend| scanner.scan_with(backend, ScanMode::Listening, Some(&range));
+
+            let netlink = scan(ScanBackend::Netlink).unwrap();
+            let proc = scan(ScanBackend::Proc).unwrap();
+
+            assert!(!proc.is_empty());
+            for entry in &proc {
+                assert!(netlink.contains(entry), "netlink missing {:?}", entry);
+            }
+            for entry in &netlink {
+                assert!(proc.contains(entry), "proc missing {:?}", entry);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_unix_line_listening_path() {
+        let line = "0000000000000000: 00000002 00000000 00010000 0001 01 23456 /run/docker.sock";
+        let entry = PortScanner::parse_unix_line(line).unwrap().unwrap();
+
+        assert_eq!(entry.path.as_deref(), Some("/run/docker.sock"));
+        assert_eq!(entry.abstract_name, None);
+        assert_eq!(entry.socket_type, UnixSocketType::Stream);
+        assert_eq!(entry.state, UnixSocketState::Unconnected);
+        assert!(entry.listening);
+        assert_eq!(entry.inode, 23456);
+    }
+
+    #[test]
+    fn test_parse_unix_line_abstract() {
+        let line = "0000000000000000: 00000002 00000000 00010000 0001 01 34567 @/tmp/.X11-unix/X0";
+        let entry = PortScanner::parse_unix_line(line).unwrap().unwrap();
+
+        assert_eq!(entry.path, None);
+        assert_eq!(entry.abstract_name.as_deref(), Some("/tmp/.X11-unix/X0"));
+    }
+
+    #[test]
+    fn test_parse_unix_line_unnamed_connected() {
+        let line = "0000000000000000: 00000003 00000000 00000000 0001 03  9007";
+        let entry = PortScanner::parse_unix_line(line).unwrap().unwrap();
+
+        assert_eq!(entry.path, None);
+        assert_eq!(entry.abstract_name, None);
+        assert_eq!(entry.state, UnixSocketState::Connected);
+        assert!(!entry.listening);
+    }
+
+    #[test]
+    fn test_parse_unix_line_path_with_spaces() {
+        let line = "0000000000000000: 00000002 00000000 00000000 0002 01 45678 /tmp/my app/socket";
+        let entry = PortScanner::parse_unix_line(line).unwrap().unwrap();
+
+        assert_eq!(entry.path.as_deref(), Some("/tmp/my app/socket"));
+        assert_eq!(entry.socket_type, UnixSocketType::Dgram);
+    }
+
+    #[test]
+    fn test_scan_unix_sees_own_listener() {
+        let dir = std::env::temp_dir().join(format!("portwatcher-unix-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.sock");
+        let _ = std::fs::remove_file(&path);
+        let _listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+
+        let entries = PortScanner::new().scan_unix(ScanMode::Listening).unwrap();
+        let found = entries
+            .iter()
+            .any(|e| e.listening && e.path.as_deref() == path.to_str());
+
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(found);
+    }
+
+    #[test]
+    fn test_parse_raw_line() {
+        // Raw ICMP socket: the "port" is the IP protocol number (1 = ICMP)
+        let line = "   1: 00000000:0001 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 22222 2 0000000000000000 0";
+        let entry = PortScanner::parse_line(
+            line,
+            Protocol::Raw,
+            AddressFamily::Ipv4,
+            ScanMode::Listening,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(entry.local_port, 1);
+        assert_eq!(entry.protocol, Protocol::Raw);
+        assert_eq!(entry.state, None);
+    }
+
+    #[test]
+    fn test_proc_table_names() {
+        assert_eq!(
+            PortScanner::proc_table_name(Protocol::Raw, AddressFamily::Ipv6),
+            Some("raw6")
+        );
+        assert_eq!(
+            PortScanner::proc_table_name(Protocol::Icmp, AddressFamily::Ipv4),
+            Some("icmp")
+        );
+        assert_eq!(
+            PortScanner::proc_table_name(Protocol::UdpLite, AddressFamily::Ipv4),
+            Some("udplite")
+        );
+        assert_eq!(
+            PortScanner::proc_table_name(Protocol::Sctp, AddressFamily::Ipv4),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_netns_link() {
+        assert_eq!(
+            PortScanner::parse_netns_link("net:[4026531840]"),
+            Some(4026531840)
+        );
+        assert_eq!(PortScanner::parse_netns_link("mnt:[4026531840]"), None);
+        assert_eq!(PortScanner::parse_netns_link("net:[abc]"), None);
+    }
+
+    #[test]
+    fn test_scan_tags_own_namespace() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let scanner = PortScanner::new();
+        let own_netns = scanner.netns_of("self");
+        let entries = scanner.scan_range(port, port, ScanMode::Listening).unwrap();
+        let entry = entries.iter().find(|e| e.protocol == Protocol::Tcp).unwrap();
+
+        assert_eq!(entry.netns, own_netns);
+    }
+
+    #[test]
+    fn test_scan_proc_dir_of_own_pid() {
+        // /proc/<pid>/net/ is the same view as /proc/net/ for our own process
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let range = PortRange {
+            start: port,
+            end: port,
+        };
+
+        let net_dir = ProcFs::default().pid_path(std::process::id(), "net");
+        let entries =
+            PortScanner::scan_proc_dir(&net_dir, ScanMode::Listening, Some(&range)).unwrap();
+
+        assert!(entries.iter().any(|e| e.local_port == port));
+    }
+
+    #[test]
+    fn test_parse_sctp_eps() {
+        let content = " ENDPT     SOCK   STY SST HBKT LPORT   UID INODE LADDRS\n\
+ffff88017e0a0200 ffff880299f7fa00 2   10  29   3868   200 299689357 10.0.0.1 10.0.0.2 \n";
+        let entries = PortScanner::parse_sctp_eps(content).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].local_address, "10.0.0.1");
+        assert_eq!(entries[0].local_port, 3868);
+        assert_eq!(entries[0].inode, 299689357);
+        assert_eq!(entries[0].uid, 200);
+        assert_eq!(entries[0].protocol, Protocol::Sctp);
+        assert_eq!(entries[0].state, Some(TcpState::Listen));
+        assert_eq!(entries[0].remote_port, 0);
+    }
+
+    #[test]
+    fn test_parse_sctp_eps_ipv6() {
+        let content = " ENDPT     SOCK   STY SST HBKT LPORT   UID INODE LADDRS\n\
+ffff88017e0a0200 ffff880299f7fa00 1   10  29   2905     0 1234 0000:0000:0000:0000:0000:0000:0000:0001 \n";
+        let entries = PortScanner::parse_sctp_eps(content).unwrap();
+
+        assert_eq!(entries[0].local_address, "::1");
+        assert_eq!(entries[0].family, AddressFamily::Ipv6);
+        assert_eq!(entries[0].remote_address, "::");
+    }
+
+    #[test]
+    fn test_parse_sctp_assocs_prefers_primary_peer() {
+        let content = " ASSOC     SOCK   STY SST ST HBKT ASSOC-ID TX_QUEUE RX_QUEUE UID INODE LPORT RPORT LADDRS <-> RADDRS HBINT INS OUTS MAXRT T1X T2X RTXC wmema wmemq sndbuf rcvbuf\n\
+ffff8801 ffff8802 2   1   3  0    2        0        0     1000 123456 3868  40000 10.0.0.1 <-> 10.0.0.3 *10.0.0.2 \t7500    10    10   10    0    0        0        1        0   212992   212992\n";
+        let entries = PortScanner::parse_sctp_assocs(content).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].local_address, "10.0.0.1");
+        assert_eq!(entries[0].local_port, 3868);
+        assert_eq!(entries[0].remote_address, "10.0.0.2");
+        assert_eq!(entries[0].remote_port, 40000);
+        assert_eq!(entries[0].inode, 123456);
+        assert_eq!(entries[0].uid, 1000);
+        assert_eq!(entries[0].state, Some(TcpState::Established));
+    }
+
+    #[test]
+    fn test_scan_sctp_without_module() {
+        // Must not fail when /proc/net/sctp/ is absent
+        assert!(PortScanner::scan_sctp(&ProcFs::default().net_dir(), ScanMode::All, None).is_ok());
     }
 }
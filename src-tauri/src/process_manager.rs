@@ -1,99 +1,826 @@
-use crate::types::KillResult;
+use crate::helper::{helper_path, HelperRequest, EXIT_USAGE, MAX_GRACE_PERIOD};
+use crate::kill_log::KillLog;
+use crate::pidfd::PidFd;
+use crate::process_resolver::ProcessResolver;
+use crate::procfs::ProcFs;
+use crate::protection::ProtectionPolicy;
+use crate::types::{
+    KillRecord, KillResult, KillStage, KillTarget, ProcessIdentity, ProcessKillResult,
+    ProcessSignal,
+};
+use nix::errno::Errno;
 use nix::sys::signal::{kill, Signal};
 use nix::unistd::Pid;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
 use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long a process gets to exit after SIGTERM before it is sent SIGKILL
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// How often a signalled process is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long to wait for the kernel to tear a process down after SIGKILL
+const SIGKILL_TIMEOUT: Duration = Duration::from_secs(1);
 
 /// Manager for process termination operations
-pub struct ProcessManager;
+///
+/// Kills are graceful: SIGTERM first, then SIGKILL for anything still running
+/// once the grace period is over. Protected processes are never signalled.
+pub struct ProcessManager {
+    /// procfs tree used to check process existence and ownership
+    procfs: ProcFs,
+    /// Time allowed between SIGTERM and SIGKILL
+    grace_period: Duration,
+    /// Whether to send SIGKILL once the grace period is over
+    escalate: bool,
+    /// Processes that are refused any signal
+    protection: ProtectionPolicy,
+    /// Where every kill and signal attempt is recorded, if anywhere
+    kill_log: Option<KillLog>,
+    /// Privileged helper run through pkexec for elevated kills and signals
+    /// (`pkexec kill` when it isn't installed)
+    helper: PathBuf,
+}
+
+/// A process as it was just before being signalled, for the kill log
+struct Snapshot {
+    name: Option<String>,
+    cmdline: Vec<String>,
+    cwd: Option<String>,
+    environ: Option<BTreeMap<String, String>>,
+}
+
+/// A process pinned for signalling
+struct Target {
+    /// Identity the process had when it was looked up
+    identity: ProcessIdentity,
+    /// Handle PID reuse can't redirect; None without kernel support
+    pidfd: Option<PidFd>,
+}
 
 impl ProcessManager {
-    /// Kill a process by PID using SIGTERM
+    /// Create a manager checking processes against the system's /proc
+    pub fn new() -> Self {
+        Self::with_procfs(ProcFs::default())
+    }
+
+    /// Create a manager checking processes against the given procfs tree
+    pub fn with_procfs(procfs: ProcFs) -> Self {
+        Self {
+            procfs,
+            grace_period: DEFAULT_GRACE_PERIOD,
+            escalate: true,
+            protection: ProtectionPolicy::new(),
+            kill_log: None,
+            helper: helper_path(),
+        }
+    }
+
+    /// Set how long a process gets to exit after SIGTERM before SIGKILL
+    pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Set which processes are refused any signal (built-in protections only by default)
+    pub fn with_protection(mut self, protection: ProtectionPolicy) -> Self {
+        self.protection = protection;
+        self
+    }
+
+    /// Record every kill and signal attempt in `kill_log`
+    pub fn with_kill_log(mut self, kill_log: KillLog) -> Self {
+        self.kill_log = Some(kill_log);
+        self
+    }
+
+    /// Run elevated kills and signals through the helper at `helper`
+    pub fn with_helper(mut self, helper: impl Into<PathBuf>) -> Self {
+        self.helper = helper.into();
+        self
+    }
+
+    /// Set whether processes still running after the grace period get SIGKILL
     ///
-    /// Returns Success if the process was killed, PermissionDenied if the caller
-    /// doesn't have permission, or an error for other failures.
-    pub fn kill(pid: u32) -> KillResult {
-        // First check if the process exists
-        if !Self::process_exists(pid) {
-            return KillResult::ProcessNotFound { pid };
+    /// Without escalation they are reported as errors and left running.
+    pub fn with_escalation(mut self, escalate: bool) -> Self {
+        self.escalate = escalate;
+        self
+    }
+
+    /// Kill a process by PID, escalating from SIGTERM to SIGKILL
+    ///
+    /// Pass the `start_time` the process was listed with to make sure the PID
+    /// still belongs to it; IdentityMismatch is returned otherwise. Returns
+    /// Success (with the signal that ended it) once the process is gone,
+    /// PermissionDenied if the caller doesn't have permission, or an error for
+    /// other failures.
+    pub fn kill(&self, pid: u32, start_time: Option<u64>) -> KillResult {
+        self.kill_target(KillTarget {
+            pid,
+            start_time,
+            port: None,
+        })
+    }
+
+    /// Kill a process as `kill` does, keeping the port it was listed under in the log
+    pub fn kill_target(&self, target: KillTarget) -> KillResult {
+        self.logged(&[target], ProcessSignal::Sigterm, false, true, |targets| {
+            self.terminate(targets)
+        })
+        .remove(0)
+    }
+
+    /// Kill a process using pkexec for elevated privileges
+    ///
+    /// This will prompt the user for their password via a graphical dialog. The
+    /// whole SIGTERM, wait, SIGKILL sequence runs in one call to the privileged
+    /// helper so the user is only asked once; the helper re-checks the process
+    /// identity before each signal. Without the helper, `pkexec kill` is used.
+    pub fn kill_elevated(&self, pid: u32, start_time: Option<u64>) -> KillResult {
+        self.kill_target_elevated(KillTarget {
+            pid,
+            start_time,
+            port: None,
+        })
+    }
+
+    /// Kill a process as `kill_elevated` does, keeping its port in the log
+    pub fn kill_target_elevated(&self, target: KillTarget) -> KillResult {
+        self.logged(&[target], ProcessSignal::Sigterm, true, true, |targets| {
+            self.terminate_elevated(targets)
+        })
+        .remove(0)
+    }
+
+    /// Send a signal to a process, without waiting for it to react
+    ///
+    /// `start_time` is checked as in `kill`.
+    pub fn send_signal(
+        &self,
+        pid: u32,
+        signal: ProcessSignal,
+        start_time: Option<u64>,
+    ) -> KillResult {
+        let target = KillTarget {
+            pid,
+            start_time,
+            port: None,
+        };
+        self.logged(&[target], signal, false, false, |_| {
+            let delivered = self
+                .target(pid, start_time)
+                .and_then(|target| self.deliver(&target, signal.into()));
+
+            vec![match delivered {
+                Ok(()) => KillResult::Signalled { signal },
+                Err(result) => result,
+            }]
+        })
+        .remove(0)
+    }
+
+    /// Send a signal to a process through the privileged helper
+    pub fn send_signal_elevated(
+        &self,
+        pid: u32,
+        signal: ProcessSignal,
+        start_time: Option<u64>,
+    ) -> KillResult {
+        let target = KillTarget {
+            pid,
+            start_time,
+            port: None,
+        };
+        self.logged(&[target], signal, true, false, |_| {
+            vec![self.signal_elevated(pid, signal, start_time)]
+        })
+        .remove(0)
+    }
+
+    /// Read a process's identity (PID and start time) from /proc/[pid]/stat
+    pub fn identify(&self, pid: u32) -> Option<ProcessIdentity> {
+        let stat = fs::read_to_string(self.procfs.pid_path(pid, "stat")).ok()?;
+        Some(ProcessIdentity {
+            pid,
+            start_time: ProcessResolver::get_stat_field(&stat, 22)?,
+        })
+    }
+
+    /// Signal a process through the privileged helper, which re-checks its identity
+    fn signal_elevated(
+        &self,
+        pid: u32,
+        signal: ProcessSignal,
+        start_time: Option<u64>,
+    ) -> KillResult {
+        let identity = match self.target(pid, start_time) {
+            Ok(target) => target.identity,
+            Err(result) => return result,
+        };
+
+        if !self.helper.is_file() {
+            return match Self::pkexec_kill(signal.into(), &[pid]) {
+                Ok(true) => KillResult::Signalled { signal },
+                Ok(false) => KillResult::ProcessNotFound { pid },
+                Err(result) => result,
+            };
         }
 
-        // Check if we own the process
-        if !Self::is_owned_by_current_user(pid) {
-            return KillResult::PermissionDenied { pid };
+        let request = HelperRequest::Signal {
+            target: identity,
+            signal,
+            protected: self.protection.names().to_vec(),
+        };
+        match self.run_helper(&request) {
+            Ok(results) => Self::helper_result(&results, pid),
+            Err(result) => result,
+        }
+    }
+
+    /// Run a request through the privileged helper, returning its per-process results
+    ///
+    /// Failures of the request as a whole are returned as a single result.
+    fn run_helper(&self, request: &HelperRequest) -> Result<Vec<ProcessKillResult>, KillResult> {
+        let output = Command::new("pkexec")
+            .arg(&self.helper)
+            .args(request.to_args())
+            .output()
+            .map_err(Self::pkexec_error)?;
+
+        Self::helper_outcome(output.status.code(), &output.stdout, &output.stderr)
+    }
+
+    /// Send a signal to processes with `pkexec kill`, for when the helper isn't installed
+    ///
+    /// kill(1) can't re-check identities as root, so callers check them right
+    /// before; a PID reused in between would still be signalled. Returns whether
+    /// every process got the signal (kill exits with 1 if one was already gone).
+    fn pkexec_kill(signal: Signal, pids: &[u32]) -> Result<bool, KillResult> {
+        let status = Command::new("pkexec")
+            .arg("kill")
+            .arg(format!("-{}", signal as i32))
+            .args(pids.iter().map(u32::to_string))
+            .status()
+            .map_err(Self::pkexec_error)?;
+
+        Self::pkexec_kill_outcome(status.code())
+    }
+
+    /// Map how `pkexec kill` exited to whether every process was signalled
+    fn pkexec_kill_outcome(code: Option<i32>) -> Result<bool, KillResult> {
+        match code {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            Some(126) => Err(KillResult::AuthenticationCancelled),
+            Some(127) => Err(KillResult::NotAuthorized),
+            Some(code) => Err(KillResult::Error {
+                message: format!("pkexec kill exited with code: {}", code),
+            }),
+            None => Err(KillResult::Error {
+                message: "pkexec kill was terminated by a signal".to_string(),
+            }),
         }
+    }
 
-        // Try to kill with SIGTERM
-        match kill(Pid::from_raw(pid as i32), Signal::SIGTERM) {
-            Ok(()) => KillResult::Success,
-            Err(nix::errno::Errno::EPERM) => KillResult::PermissionDenied { pid },
-            Err(nix::errno::Errno::ESRCH) => KillResult::ProcessNotFound { pid },
-            Err(e) => KillResult::Error {
-                message: format!("Failed to kill process {}: {}", pid, e),
+    /// Describe a failure to start pkexec
+    fn pkexec_error(error: std::io::Error) -> KillResult {
+        KillResult::Error {
+            message: match error.kind() {
+                ErrorKind::NotFound => "pkexec not found. Please install policykit-1".to_string(),
+                _ => format!("Failed to execute pkexec: {}", error),
             },
         }
     }
 
-    /// Kill a process using pkexec for elevated privileges
+    /// Map how pkexec and the helper exited to the helper's results or a failure
+    ///
+    /// pkexec exits with 126 when the dialog is dismissed and 127 when polkit
+    /// refuses; the helper itself exits with 0 or `EXIT_USAGE`.
+    fn helper_outcome(
+        code: Option<i32>,
+        stdout: &[u8],
+        stderr: &[u8],
+    ) -> Result<Vec<ProcessKillResult>, KillResult> {
+        match code {
+            Some(0) => serde_json::from_slice(stdout).map_err(|e| KillResult::Error {
+                message: format!("Unreadable output from the privileged helper: {}", e),
+            }),
+            Some(126) => Err(KillResult::AuthenticationCancelled),
+            Some(127) => Err(KillResult::NotAuthorized),
+            Some(EXIT_USAGE) => Err(KillResult::Error {
+                message: format!(
+                    "Privileged helper refused the request: {}",
+                    String::from_utf8_lossy(stderr).trim()
+                ),
+            }),
+            Some(code) => Err(KillResult::Error {
+                message: format!("Privileged helper exited with code: {}", code),
+            }),
+            None => Err(KillResult::Error {
+                message: "Privileged helper was terminated by a signal".to_string(),
+            }),
+        }
+    }
+
+    /// The helper's result for `pid`, an Error if it didn't report one
+    fn helper_result(results: &[ProcessKillResult], pid: u32) -> KillResult {
+        results
+            .iter()
+            .find(|r| r.pid == pid)
+            .map(|r| r.result.clone())
+            .unwrap_or_else(|| KillResult::Error {
+                message: format!("No result for process {} from the privileged helper", pid),
+            })
+    }
+
+    /// Terminate processes as root, all in a single helper invocation
+    ///
+    /// Same sequence and results as `terminate`, for one password prompt per batch.
+    fn terminate_elevated(&self, targets: &[KillTarget]) -> Vec<KillResult> {
+        let mut results: Vec<Option<KillResult>> = Vec::with_capacity(targets.len());
+        let mut identities = Vec::new();
+        for target in targets {
+            match self.target(target.pid, target.start_time) {
+                Ok(pinned) => {
+                    identities.push(pinned.identity);
+                    results.push(None);
+                }
+                Err(result) => results.push(Some(result)),
+            }
+        }
+
+        let outcome = if identities.is_empty() {
+            Ok(Vec::new())
+        } else if !self.helper.is_file() {
+            self.terminate_with_pkexec_kill(&identities)
+        } else {
+            self.run_helper(&HelperRequest::Kill {
+                targets: identities,
+                grace_period: self.grace_period.min(MAX_GRACE_PERIOD),
+                escalate: self.escalate,
+                protected: self.protection.names().to_vec(),
+            })
+        };
+
+        targets
+            .iter()
+            .zip(results)
+            .map(|(target, result)| {
+                result.unwrap_or_else(|| match &outcome {
+                    Ok(reported) => Self::helper_result(reported, target.pid),
+                    Err(result) => result.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Terminate processes as root with `pkexec kill`, for when the helper isn't installed
     ///
-    /// This will prompt the user for their password via a graphical dialog.
-    pub fn kill_elevated(pid: u32) -> KillResult {
+    /// Used by the AppImage, which can't install the helper. Same sequence as the
+    /// helper, but without its identity check as root (see `pkexec_kill`), and
+    /// escalating to SIGKILL asks for the password a second time.
+    fn terminate_with_pkexec_kill(
+        &self,
+        identities: &[ProcessIdentity],
+    ) -> Result<Vec<ProcessKillResult>, KillResult> {
+        let watched: Vec<Option<ProcessIdentity>> = identities.iter().copied().map(Some).collect();
+        let mut results: Vec<Option<KillResult>> = vec![None; identities.len()];
+        let running = |results: &[Option<KillResult>]| -> Vec<u32> {
+            identities
+                .iter()
+                .zip(results)
+                .filter(|(identity, result)| result.is_none() && self.is_running(identity))
+                .map(|(identity, _)| identity.pid)
+                .collect()
+        };
+
+        let pids = running(&results);
+        if !pids.is_empty() {
+            Self::pkexec_kill(Signal::SIGTERM, &pids)?;
+        }
+        let deadline = Instant::now() + self.grace_period;
+        self.poll_until(&watched, &mut results, deadline, KillStage::Sigterm);
+
+        let mut last_stage = "SIGTERM";
+        let pids = running(&results);
+        if self.escalate && !pids.is_empty() {
+            last_stage = "SIGKILL";
+            if let Err(failure) = Self::pkexec_kill(Signal::SIGKILL, &pids) {
+                for result in results.iter_mut().filter(|result| result.is_none()) {
+                    *result = Some(failure.clone());
+                }
+            }
+            let deadline = Instant::now() + SIGKILL_TIMEOUT;
+            self.poll_until(&watched, &mut results, deadline, KillStage::Sigkill);
+        }
+
+        Ok(identities
+            .iter()
+            .zip(results)
+            .map(|(identity, result)| ProcessKillResult {
+                pid: identity.pid,
+                result: result.unwrap_or_else(|| KillResult::Error {
+                    message: format!(
+                        "Process {} is still running after {}",
+                        identity.pid, last_stage
+                    ),
+                }),
+            })
+            .collect())
+    }
+
+    /// Terminate processes, returning one result per target in the same order
+    ///
+    /// All of them get SIGTERM before any grace period starts, so a tree shares
+    /// a single wait instead of one per process.
+    fn terminate(&self, targets: &[KillTarget]) -> Vec<KillResult> {
+        let mut pinned = Vec::with_capacity(targets.len());
+        let mut results = Vec::with_capacity(targets.len());
+
+        for target in targets {
+            let signalled = self
+                .target(target.pid, target.start_time)
+                .and_then(|target| self.send_sigterm(&target).map(|()| target));
+            match signalled {
+                Ok(target) => {
+                    pinned.push(Some(target));
+                    results.push(None);
+                }
+                Err(result) => {
+                    pinned.push(None);
+                    results.push(Some(result));
+                }
+            }
+        }
+
+        // Wait for the processes that accepted SIGTERM
+        let watched: Vec<Option<ProcessIdentity>> = pinned
+            .iter()
+            .map(|t| t.as_ref().map(|t| t.identity))
+            .collect();
+        let deadline = Instant::now() + self.grace_period;
+        self.poll_until(&watched, &mut results, deadline, KillStage::Sigterm);
+
+        if !self.escalate {
+            return targets
+                .iter()
+                .zip(results)
+                .map(|(target, result)| {
+                    result.unwrap_or_else(|| KillResult::Error {
+                        message: format!("Process {} is still running after SIGTERM", target.pid),
+                    })
+                })
+                .collect();
+        }
+
+        // Escalate on whatever is still running
+        for (target, result) in pinned.iter().zip(results.iter_mut()) {
+            let Some(target) = target.as_ref().filter(|_| result.is_none()) else {
+                continue;
+            };
+            match self.signal(target, Signal::SIGKILL) {
+                Ok(()) => {}
+                // Exited between the last poll and SIGKILL
+                Err(KillResult::ProcessNotFound { .. }) => {
+                    *result = Some(KillResult::Success {
+                        stage: KillStage::Sigterm,
+                    })
+                }
+                Err(e) => *result = Some(e),
+            }
+        }
+
+        let deadline = Instant::now() + SIGKILL_TIMEOUT;
+        self.poll_until(&watched, &mut results, deadline, KillStage::Sigkill);
+
+        // Still there after SIGKILL: stuck in the kernel (e.g. uninterruptible I/O)
+        targets
+            .iter()
+            .zip(results)
+            .map(|(target, result)| {
+                result.unwrap_or_else(|| KillResult::Error {
+                    message: format!("Process {} did not exit after SIGKILL", target.pid),
+                })
+            })
+            .collect()
+    }
+
+    /// Look a process up and pin it for signalling
+    ///
+    /// Fails with Protected for processes of the protection policy, and with
+    /// IdentityMismatch if `start_time` is given and the PID now belongs to a
+    /// process that started at another time.
+    fn target(&self, pid: u32, start_time: Option<u64>) -> Result<Target, KillResult> {
         // First check if the process exists
-        if !Self::process_exists(pid) {
-            return KillResult::ProcessNotFound { pid };
+        if !self.process_exists(pid) {
+            return Err(KillResult::ProcessNotFound { pid });
         }
 
-        // Use pkexec to run kill with elevated privileges
-        let result = Command::new("pkexec")
-            .arg("kill")
-            .arg("-TERM")
-            .arg(pid.to_string())
-            .status();
-
-        match result {
-            Ok(status) => {
-                if status.success() {
-                    KillResult::Success
-                } else {
-                    match status.code() {
-                        Some(126) => {
-                            // Authentication dialog dismissed
-                            KillResult::Error {
-                                message: "Authentication cancelled".to_string(),
-                            }
-                        }
-                        Some(127) => {
-                            // pkexec not found
-                            KillResult::Error {
-                                message: "pkexec not found. Please install policykit-1".to_string(),
-                            }
-                        }
-                        Some(1) => {
-                            // Process may have exited
-                            if Self::process_exists(pid) {
-                                KillResult::Error {
-                                    message: format!("Failed to kill process {}", pid),
-                                }
-                            } else {
-                                // Process is gone, consider it a success
-                                KillResult::Success
-                            }
-                        }
-                        _ => KillResult::Error {
-                            message: format!("pkexec exited with code: {:?}", status.code()),
-                        },
+        if let Some(reason) = self.protection.check(&self.procfs, pid) {
+            return Err(KillResult::Protected { pid, reason });
+        }
+
+        // Open the pidfd before reading the start time: if that still matches,
+        // the pidfd refers to the expected process however the PID is reused later.
+        // A pidfd only names PIDs of our own namespace, so not for a foreign root.
+        let pidfd = if self.procfs.is_system() {
+            match PidFd::open(pid) {
+                Ok(pidfd) => Some(pidfd),
+                Err(Errno::ESRCH) => return Err(KillResult::ProcessNotFound { pid }),
+                Err(_) => None, // No pidfd support, fall back to kill()
+            }
+        } else {
+            None
+        };
+
+        let identity = self
+            .identify(pid)
+            .ok_or(KillResult::ProcessNotFound { pid })?;
+        if start_time.is_some_and(|expected| expected != identity.start_time) {
+            return Err(KillResult::IdentityMismatch { pid });
+        }
+
+        Ok(Target { identity, pidfd })
+    }
+
+    /// Send SIGTERM, then SIGCONT so a stopped process can act on it
+    fn send_sigterm(&self, target: &Target) -> Result<(), KillResult> {
+        self.deliver(target, Signal::SIGTERM)?;
+        let _ = self.signal(target, Signal::SIGCONT);
+        Ok(())
+    }
+
+    /// Check that we own a process and send it a signal
+    fn deliver(&self, target: &Target, signal: Signal) -> Result<(), KillResult> {
+        let pid = target.identity.pid;
+
+        // Check if we own the process; root (the privileged helper) may signal any
+        if users::get_current_uid() != 0 && !self.is_owned_by_current_user(pid) {
+            return Err(KillResult::PermissionDenied { pid });
+        }
+
+        self.signal(target, signal)
+    }
+
+    /// Send a signal to a pinned process, through its pidfd when there is one
+    fn signal(&self, target: &Target, signal: Signal) -> Result<(), KillResult> {
+        let pid = target.identity.pid;
+
+        let sent = match &target.pidfd {
+            Some(pidfd) => pidfd.send_signal(signal),
+            None => {
+                // Without a pidfd, re-check right before signalling to keep the
+                // window in which the PID could be reused as small as possible
+                if self.identify(pid) != Some(target.identity) {
+                    return Err(KillResult::ProcessNotFound { pid });
+                }
+                kill(Pid::from_raw(pid as i32), signal)
+            }
+        };
+
+        match sent {
+            Ok(()) => Ok(()),
+            Err(Errno::EPERM) => Err(KillResult::PermissionDenied { pid }),
+            Err(Errno::ESRCH) => Err(KillResult::ProcessNotFound { pid }),
+            Err(e) => Err(KillResult::Error {
+                message: format!("Failed to send {} to process {}: {}", signal, pid, e),
+            }),
+        }
+    }
+
+    /// Poll pending processes until they have all exited or `deadline` passes
+    ///
+    /// Each one that exits is recorded as ended by `stage`.
+    fn poll_until(
+        &self,
+        identities: &[Option<ProcessIdentity>],
+        results: &mut [Option<KillResult>],
+        deadline: Instant,
+        stage: KillStage,
+    ) {
+        loop {
+            for (identity, result) in identities.iter().zip(results.iter_mut()) {
+                if let (Some(identity), None) = (identity, &result) {
+                    if !self.is_running(identity) {
+                        *result = Some(KillResult::Success { stage });
                     }
                 }
             }
-            Err(e) => KillResult::Error {
-                message: format!("Failed to execute pkexec: {}", e),
-            },
+
+            if results.iter().all(Option::is_some) || Instant::now() >= deadline {
+                return;
+            }
+            thread::sleep(POLL_INTERVAL);
         }
     }
 
+    /// Kill a process and all of its descendants
+    ///
+    /// The whole tree is captured before any signal is sent, then signalled from
+    /// the root down so a supervisor (`npm run dev`, a shell loop) is gone before
+    /// it can restart the children it loses. Each member is pinned to the start
+    /// time read during the walk, so a PID reused in between is left alone.
+    pub fn kill_tree(&self, pid: u32) -> Vec<ProcessKillResult> {
+        let members = self.subtree(pid);
+        if members.is_empty() {
+            return vec![ProcessKillResult {
+                pid,
+                result: KillResult::ProcessNotFound { pid },
+            }];
+        }
+        self.kill_members(&members)
+    }
+
+    /// Kill every process in the process group of `pid`
+    ///
+    /// Catches members that were reparented away from the tree (a daemonized
+    /// watcher, a backgrounded job) but still share the group leader's PGID.
+    pub fn kill_group(&self, pid: u32) -> Vec<ProcessKillResult> {
+        let Some(pgid) = self.process_group(pid) else {
+            return vec![ProcessKillResult {
+                pid,
+                result: KillResult::ProcessNotFound { pid },
+            }];
+        };
+
+        let mut members: Vec<ProcessIdentity> = ProcessResolver::list_pids(&self.procfs)
+            .into_iter()
+            .filter(|&member| self.process_group(member) == Some(pgid))
+            .filter_map(|member| self.identify(member))
+            .collect();
+
+        // Group leader first, for the same reason kill_tree goes top-down
+        members.sort_by_key(|member| (member.pid != pgid, member.pid));
+
+        self.kill_members(&members)
+    }
+
+    /// Kill several processes together, signalled in order
+    ///
+    /// Each target's start time is checked as in `kill`. They share a single
+    /// grace period. Every target gets a result, Protected for PortWatcher itself.
+    pub fn kill_all(&self, targets: &[KillTarget]) -> Vec<ProcessKillResult> {
+        let results = self.logged(targets, ProcessSignal::Sigterm, false, false, |targets| {
+            self.terminate(targets)
+        });
+        Self::per_process(targets, results)
+    }
+
+    /// Kill several processes using a single pkexec prompt for the whole batch
+    pub fn kill_all_elevated(&self, targets: &[KillTarget]) -> Vec<ProcessKillResult> {
+        let results = self.logged(targets, ProcessSignal::Sigterm, true, false, |targets| {
+            self.terminate_elevated(targets)
+        });
+        Self::per_process(targets, results)
+    }
+
+    /// Kill processes captured by a walk, each only if it still has the start time seen then
+    fn kill_members(&self, members: &[ProcessIdentity]) -> Vec<ProcessKillResult> {
+        let targets: Vec<KillTarget> = members
+            .iter()
+            .map(|member| KillTarget {
+                pid: member.pid,
+                start_time: Some(member.start_time),
+                port: None,
+            })
+            .collect();
+        self.kill_all(&targets)
+    }
+
+    /// Run a kill or signal operation, recording each target's attempt in the kill log
+    ///
+    /// The environment is only kept for `relaunchable` kills, since it can hold
+    /// secrets. Logging is best effort: failing to write the history never stops
+    /// a kill.
+    fn logged(
+        &self,
+        targets: &[KillTarget],
+        signal: ProcessSignal,
+        elevated: bool,
+        relaunchable: bool,
+        operation: impl FnOnce(&[KillTarget]) -> Vec<KillResult>,
+    ) -> Vec<KillResult> {
+        let Some(kill_log) = &self.kill_log else {
+            return operation(targets);
+        };
+
+        // Read before signalling: a killed process leaves nothing behind
+        let snapshots: Vec<Snapshot> = targets
+            .iter()
+            .map(|target| self.snapshot(target.pid, relaunchable))
+            .collect();
+        let results = operation(targets);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        for (i, ((target, snapshot), outcome)) in
+            targets.iter().zip(snapshots).zip(&results).enumerate()
+        {
+            let _ = kill_log.append(&KillRecord {
+                // Records of a batch share the instant, so number them within it
+                id: now.as_nanos() as u64 + i as u64,
+                timestamp: now.as_millis() as u64,
+                pid: target.pid,
+                name: snapshot.name,
+                cmdline: snapshot.cmdline,
+                cwd: snapshot.cwd,
+                environ: snapshot.environ,
+                port: target.port,
+                signal,
+                elevated,
+                outcome: outcome.clone(),
+            });
+        }
+        results
+    }
+
+    /// What the kill log keeps of a process, with its environment if it may be relaunched
+    fn snapshot(&self, pid: u32, with_environ: bool) -> Snapshot {
+        Snapshot {
+            name: fs::read_to_string(self.procfs.pid_path(pid, "comm"))
+                .ok()
+                .map(|comm| comm.trim().to_string()),
+            cmdline: ProcessResolver::get_cmdline(&self.procfs, pid),
+            cwd: fs::read_link(self.procfs.pid_path(pid, "cwd"))
+                .ok()
+                .map(|cwd| cwd.to_string_lossy().into_owned()),
+            environ: with_environ
+                .then(|| ProcessResolver::get_environ(&self.procfs, pid))
+                .flatten(),
+        }
+    }
+
+    /// Pair each target with its result
+    fn per_process(targets: &[KillTarget], results: Vec<KillResult>) -> Vec<ProcessKillResult> {
+        targets
+            .iter()
+            .zip(results)
+            .map(|(target, result)| ProcessKillResult {
+                pid: target.pid,
+                result,
+            })
+            .collect()
+    }
+
+    /// A process followed by its descendants, breadth-first from the root
+    ///
+    /// Empty when the root no longer exists. Processes that exit during the
+    /// walk are left out.
+    fn subtree(&self, root: u32) -> Vec<ProcessIdentity> {
+        let Some(root) = self.identify(root) else {
+            return Vec::new();
+        };
+
+        let mut children: HashMap<u32, Vec<ProcessIdentity>> = HashMap::new();
+        for pid in ProcessResolver::list_pids(&self.procfs) {
+            if let (Some(ppid), Some(identity)) = (self.parent_of(pid), self.identify(pid)) {
+                children.entry(ppid).or_default().push(identity);
+            }
+        }
+
+        let mut tree = vec![root];
+        let mut queue = VecDeque::from([root.pid]);
+
+        while let Some(pid) = queue.pop_front() {
+            let Some(kids) = children.get_mut(&pid) else {
+                continue;
+            };
+            kids.sort_unstable_by_key(|kid| kid.pid);
+            for &child in kids.iter() {
+                // Guards against a cycle from a pid reused while we were reading
+                if !tree.iter().any(|member| member.pid == child.pid) {
+                    tree.push(child);
+                    queue.push_back(child.pid);
+                }
+            }
+        }
+
+        tree
+    }
+
+    /// Read a process's parent PID from /proc/[pid]/status
+    fn parent_of(&self, pid: u32) -> Option<u32> {
+        let status = fs::read_to_string(self.procfs.pid_path(pid, "status")).ok()?;
+        ProcessResolver::get_status_field(&status, "PPid:")
+    }
+
+    /// Read a process's group ID (field 5) from /proc/[pid]/stat
+    fn process_group(&self, pid: u32) -> Option<u32> {
+        let stat = fs::read_to_string(self.procfs.pid_path(pid, "stat")).ok()?;
+        ProcessResolver::get_stat_field(&stat, 5).and_then(|pgid| u32::try_from(pgid).ok())
+    }
+
     /// Check if a process is owned by the current user
-    pub fn is_owned_by_current_user(pid: u32) -> bool {
-        let status_path = format!("/proc/{}/status", pid);
+    pub fn is_owned_by_current_user(&self, pid: u32) -> bool {
+        let status_path = self.procfs.pid_path(pid, "status");
         let content = match fs::read_to_string(&status_path) {
             Ok(c) => c,
             Err(_) => return false,
@@ -116,34 +843,72 @@ impl ProcessManager {
     }
 
     /// Check if a process exists
-    fn process_exists(pid: u32) -> bool {
-        let proc_path = format!("/proc/{}", pid);
-        std::path::Path::new(&proc_path).exists()
+    fn process_exists(&self, pid: u32) -> bool {
+        self.procfs.pid_dir(pid).exists()
+    }
+
+    /// Check if a process is still alive and is the one identified
+    ///
+    /// A zombie keeps its /proc entry until its parent reaps it, but it has
+    /// already released its sockets. A different start time means the original
+    /// process is gone and its PID was reused.
+    fn is_running(&self, identity: &ProcessIdentity) -> bool {
+        let Ok(stat) = fs::read_to_string(self.procfs.pid_path(identity.pid, "stat")) else {
+            return false;
+        };
+
+        // State (field 3) follows the parenthesized name
+        let state = stat
+            .rfind(')')
+            .and_then(|i| stat[i + 1..].split_whitespace().next());
+        let start_time = ProcessResolver::get_stat_field(&stat, 22);
+
+        !matches!(state, Some("Z") | Some("X") | None) && start_time == Some(identity.start_time)
+    }
+}
+
+impl From<ProcessSignal> for Signal {
+    fn from(signal: ProcessSignal) -> Self {
+        match signal {
+            ProcessSignal::Sigterm => Signal::SIGTERM,
+            ProcessSignal::Sigint => Signal::SIGINT,
+            ProcessSignal::Sighup => Signal::SIGHUP,
+            ProcessSignal::Sigkill => Signal::SIGKILL,
+            ProcessSignal::Sigstop => Signal::SIGSTOP,
+            ProcessSignal::Sigcont => Signal::SIGCONT,
+        }
+    }
+}
+
+impl Default for ProcessManager {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::ProtectionReason;
 
     #[test]
     fn test_process_exists_self() {
         // Our own process should exist
         let pid = std::process::id();
-        assert!(ProcessManager::process_exists(pid));
+        assert!(ProcessManager::new().process_exists(pid));
     }
 
     #[test]
     fn test_process_exists_invalid() {
         // A very high PID should not exist
-        assert!(!ProcessManager::process_exists(999999999));
+        assert!(!ProcessManager::new().process_exists(999999999));
     }
 
     #[test]
     fn test_is_owned_by_current_user_self() {
         // Our own process should be owned by current user
         let pid = std::process::id();
-        assert!(ProcessManager::is_owned_by_current_user(pid));
+        assert!(ProcessManager::new().is_owned_by_current_user(pid));
     }
 
     #[test]
@@ -151,13 +916,13 @@ mod tests {
         // Process 1 (init/systemd) is typically owned by root
         // Unless we're running as root, this should return false
         if users::get_current_uid() != 0 {
-            assert!(!ProcessManager::is_owned_by_current_user(1));
+            assert!(!ProcessManager::new().is_owned_by_current_user(1));
         }
     }
 
     #[test]
     fn test_kill_nonexistent() {
-        let result = ProcessManager::kill(999999999);
+        let result = ProcessManager::new().kill(999999999, None);
         matches!(result, KillResult::ProcessNotFound { .. });
     }
 
@@ -165,8 +930,439 @@ mod tests {
     fn test_kill_not_owned() {
         // Process 1 is typically not owned by regular users
         if users::get_current_uid() != 0 {
-            let result = ProcessManager::kill(1);
+            let result = ProcessManager::new().kill(1, None);
             matches!(result, KillResult::PermissionDenied { .. });
         }
     }
+
+    #[test]
+    fn test_kill_exits_on_sigterm() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+
+        let result = ProcessManager::new().kill(child.id(), None);
+        child.wait().unwrap();
+
+        assert!(matches!(
+            result,
+            KillResult::Success {
+                stage: KillStage::Sigterm
+            }
+        ));
+    }
+
+    /// Spawn a shell that ignores SIGTERM, and wait until the trap is in place
+    fn spawn_ignoring_term() -> std::process::Child {
+        let child = Command::new("sh")
+            .args(["-c", "trap '' TERM; while :; do sleep 0.05; done"])
+            .spawn()
+            .unwrap();
+
+        // Bit 15 (SIGTERM) of SigIgn
+        let ignores_term = || {
+            fs::read_to_string(format!("/proc/{}/status", child.id()))
+                .ok()
+                .and_then(|status| {
+                    let mask = status.lines().find_map(|l| l.strip_prefix("SigIgn:"))?;
+                    u64::from_str_radix(mask.trim(), 16).ok()
+                })
+                .is_some_and(|mask| mask & (1 << 14) != 0)
+        };
+        for _ in 0..100 {
+            if ignores_term() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        child
+    }
+
+    #[test]
+    fn test_kill_escalates_to_sigkill() {
+        let manager = ProcessManager::new().with_grace_period(Duration::from_millis(200));
+        let mut child = spawn_ignoring_term();
+
+        let result = manager.kill(child.id(), None);
+        child.wait().unwrap();
+
+        assert!(matches!(
+            result,
+            KillResult::Success {
+                stage: KillStage::Sigkill
+            }
+        ));
+    }
+
+    #[test]
+    fn test_kill_without_escalation_leaves_process_running() {
+        let manager = ProcessManager::new()
+            .with_grace_period(Duration::from_millis(200))
+            .with_escalation(false);
+        let mut child = spawn_ignoring_term();
+
+        let result = manager.kill(child.id(), None);
+        let identity = manager.identify(child.id()).unwrap();
+        let still_running = manager.is_running(&identity);
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        assert!(matches!(result, KillResult::Error { .. }));
+        assert!(still_running);
+    }
+
+    #[test]
+    fn test_send_signal_stop_and_continue() {
+        let manager = ProcessManager::new();
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+
+        let state = || {
+            let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap();
+            stat[stat.rfind(')').unwrap() + 2..].chars().next().unwrap()
+        };
+        let wait_for_state = |want: char| {
+            for _ in 0..100 {
+                if state() == want {
+                    return true;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            false
+        };
+
+        assert!(matches!(
+            manager.send_signal(pid, ProcessSignal::Sigstop, None),
+            KillResult::Signalled {
+                signal: ProcessSignal::Sigstop
+            }
+        ));
+        assert!(wait_for_state('T'));
+
+        manager.send_signal(pid, ProcessSignal::Sigcont, None);
+        assert!(wait_for_state('S'));
+
+        // A stopped process still gets to exit on SIGTERM rather than being escalated
+        manager.send_signal(pid, ProcessSignal::Sigstop, None);
+        assert!(wait_for_state('T'));
+        let result = manager.kill(pid, None);
+        child.wait().unwrap();
+        assert!(matches!(
+            result,
+            KillResult::Success {
+                stage: KillStage::Sigterm
+            }
+        ));
+    }
+
+    #[test]
+    fn test_kill_refuses_protected_processes() {
+        let manager = ProcessManager::new();
+        let own_pid = std::process::id();
+
+        assert!(matches!(
+            manager.kill(1, None),
+            KillResult::Protected {
+                pid: 1,
+                reason: ProtectionReason::Init
+            }
+        ));
+        assert!(matches!(
+            manager.send_signal(own_pid, ProcessSignal::Sigstop, None),
+            KillResult::Protected {
+                reason: ProtectionReason::PortWatcher,
+                ..
+            }
+        ));
+
+        // Configured names are refused too, and the process is left alone
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let manager =
+            manager.with_protection(ProtectionPolicy::new().with_names(["sleep".to_string()]));
+        let results = manager.kill_all(&[KillTarget {
+            pid: child.id(),
+            start_time: None,
+            port: None,
+        }]);
+        assert!(matches!(
+            results[0].result,
+            KillResult::Protected {
+                reason: ProtectionReason::Configured,
+                ..
+            }
+        ));
+        assert!(manager.process_exists(child.id()));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_kill_all_reports_own_process() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let own_pid = std::process::id();
+        let targets: Vec<KillTarget> = [own_pid, child.id()]
+            .iter()
+            .map(|&pid| KillTarget {
+                pid,
+                start_time: None,
+                port: None,
+            })
+            .collect();
+
+        let results = ProcessManager::new().kill_all(&targets);
+        child.wait().unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].pid, own_pid);
+        assert!(matches!(
+            results[0].result,
+            KillResult::Protected {
+                reason: ProtectionReason::PortWatcher,
+                ..
+            }
+        ));
+        assert!(matches!(results[1].result, KillResult::Success { .. }));
+    }
+
+    #[test]
+    fn test_kill_is_logged() {
+        let dir = std::env::temp_dir().join(format!("portwatcher-pm-log-{}", std::process::id()));
+        let kill_log = KillLog::new(dir.join("kills.jsonl"));
+        let manager = ProcessManager::new().with_kill_log(kill_log.clone());
+
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        // The arguments show up only once the exec is through
+        let deadline = Instant::now() + Duration::from_secs(1);
+        while ProcessResolver::get_cmdline(&manager.procfs, child.id()).is_empty()
+            && Instant::now() < deadline
+        {
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        let result = manager.kill_target(KillTarget {
+            pid: child.id(),
+            start_time: None,
+            port: Some(3000),
+        });
+        child.wait().unwrap();
+        assert!(matches!(result, KillResult::Success { .. }));
+
+        // Refused attempts are recorded too
+        manager.send_signal(1, ProcessSignal::Sigstop, None);
+
+        // Signals can't be relaunched from, so no environment is kept for them
+        let mut other = Command::new("sleep").arg("30").spawn().unwrap();
+        manager.send_signal(other.id(), ProcessSignal::Sigcont, None);
+        other.kill().unwrap();
+        other.wait().unwrap();
+
+        let history = kill_log.recent(5);
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0].signal, ProcessSignal::Sigcont);
+        assert_eq!(history[0].environ, None);
+        assert_eq!(history[1].signal, ProcessSignal::Sigstop);
+        assert!(matches!(history[1].outcome, KillResult::Protected { .. }));
+
+        let kill = &history[2];
+        assert_eq!(kill.pid, child.id());
+        assert_eq!(kill.name.as_deref(), Some("sleep"));
+        assert_eq!(kill.cmdline, vec!["sleep", "30"]);
+        assert!(kill.cwd.is_some());
+        assert!(kill.environ.as_ref().unwrap().contains_key("PATH"));
+        assert_eq!(kill.port, Some(3000));
+        assert!(!kill.elevated);
+        assert!(matches!(kill.outcome, KillResult::Success { .. }));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_kill_refuses_reused_pid() {
+        let manager = ProcessManager::new();
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let identity = manager.identify(child.id()).unwrap();
+
+        // Listed with another start time: the PID now belongs to someone else
+        let result = manager.kill(identity.pid, Some(identity.start_time + 1));
+        assert!(matches!(result, KillResult::IdentityMismatch { pid } if pid == identity.pid));
+        assert!(manager.is_running(&identity));
+
+        let result = manager.kill(identity.pid, Some(identity.start_time));
+        child.wait().unwrap();
+        assert!(matches!(result, KillResult::Success { .. }));
+        assert!(!manager.is_running(&identity));
+    }
+
+    #[test]
+    fn test_elevated_kill_refuses_configured_protection() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let manager = ProcessManager::new()
+            .with_protection(ProtectionPolicy::new().with_names(["sleep".to_string()]))
+            .with_helper("/nonexistent/portwatcher-helper");
+
+        // Refused before any password prompt, with or without the helper
+        let expected = |result: &KillResult| {
+            matches!(
+                result,
+                KillResult::Protected {
+                    reason: ProtectionReason::Configured,
+                    ..
+                }
+            )
+        };
+        assert!(expected(&manager.kill_elevated(child.id(), None)));
+        assert!(expected(&manager.send_signal_elevated(
+            child.id(),
+            ProcessSignal::Sighup,
+            None
+        )));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_pkexec_kill_outcome() {
+        assert!(matches!(
+            ProcessManager::pkexec_kill_outcome(Some(0)),
+            Ok(true)
+        ));
+        // A process exited before kill got to it
+        assert!(matches!(
+            ProcessManager::pkexec_kill_outcome(Some(1)),
+            Ok(false)
+        ));
+        assert!(matches!(
+            ProcessManager::pkexec_kill_outcome(Some(126)),
+            Err(KillResult::AuthenticationCancelled)
+        ));
+        assert!(matches!(
+            ProcessManager::pkexec_kill_outcome(Some(127)),
+            Err(KillResult::NotAuthorized)
+        ));
+        assert!(matches!(
+            ProcessManager::pkexec_kill_outcome(None),
+            Err(KillResult::Error { .. })
+        ));
+    }
+
+    #[test]
+    fn test_helper_outcome() {
+        let stdout = br#"[{"pid":4242,"result":{"status":"Success","stage":"Sigterm"}}]"#;
+        let results = ProcessManager::helper_outcome(Some(0), stdout, b"").unwrap();
+        assert_eq!(results[0].pid, 4242);
+        assert!(matches!(
+            results[0].result,
+            KillResult::Success {
+                stage: KillStage::Sigterm
+            }
+        ));
+
+        assert!(matches!(
+            ProcessManager::helper_outcome(Some(126), b"", b""),
+            Err(KillResult::AuthenticationCancelled)
+        ));
+        assert!(matches!(
+            ProcessManager::helper_outcome(Some(127), b"", b""),
+            Err(KillResult::NotAuthorized)
+        ));
+        assert!(matches!(
+            ProcessManager::helper_outcome(Some(EXIT_USAGE), b"", b"bad target\n"),
+            Err(KillResult::Error { ref message }) if message.ends_with("bad target")
+        ));
+        for (code, stdout) in [(Some(0), &b"not json"[..]), (Some(1), b""), (None, b"")] {
+            assert!(matches!(
+                ProcessManager::helper_outcome(code, stdout, b""),
+                Err(KillResult::Error { .. })
+            ));
+        }
+    }
+
+    /// Spawn a shell that starts two sleeping children, and wait until both exist
+    fn spawn_tree(manager: &ProcessManager, own_group: bool) -> std::process::Child {
+        use std::os::unix::process::CommandExt;
+
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 30 & sleep 30 & wait"]);
+        if own_group {
+            command.process_group(0);
+        }
+        let child = command.spawn().unwrap();
+
+        for _ in 0..100 {
+            if manager.subtree(child.id()).len() == 3 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        child
+    }
+
+    #[test]
+    fn test_kill_tree() {
+        let manager = ProcessManager::new();
+        let mut child = spawn_tree(&manager, false);
+
+        let results = manager.kill_tree(child.id());
+        child.wait().unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].pid, child.id());
+        assert!(results
+            .iter()
+            .all(|r| matches!(r.result, KillResult::Success { .. })));
+    }
+
+    #[test]
+    fn test_kill_group() {
+        let manager = ProcessManager::new();
+        let mut child = spawn_tree(&manager, true);
+        let tree: Vec<u32> = manager.subtree(child.id()).iter().map(|m| m.pid).collect();
+
+        let results = manager.kill_group(child.id());
+        child.wait().unwrap();
+
+        // Leader first, then exactly the processes it started
+        assert_eq!(results[0].pid, child.id());
+        let mut killed: Vec<u32> = results.iter().map(|r| r.pid).collect();
+        killed.sort_unstable();
+        let mut expected = tree;
+        expected.sort_unstable();
+        assert_eq!(killed, expected);
+    }
+
+    #[test]
+    fn test_kill_tree_pins_member_start_times() {
+        let manager = ProcessManager::new();
+        let mut child = spawn_tree(&manager, false);
+
+        let tree = manager.subtree(child.id());
+        assert_eq!(tree.len(), 3);
+        for member in &tree {
+            assert_eq!(manager.identify(member.pid), Some(*member));
+        }
+
+        // A member whose PID now belongs to another process is refused
+        let stale = ProcessIdentity {
+            start_time: tree[1].start_time + 1,
+            ..tree[1]
+        };
+        let results = manager.kill_members(&[stale]);
+        assert!(matches!(
+            results[0].result,
+            KillResult::IdentityMismatch { .. }
+        ));
+
+        manager.kill_members(&tree);
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_kill_tree_nonexistent() {
+        let results = ProcessManager::new().kill_tree(999999999);
+        assert_eq!(results.len(), 1);
+        assert!(matches!(
+            results[0].result,
+            KillResult::ProcessNotFound { pid: 999999999 }
+        ));
+    }
 }
@@ -1,86 +1,380 @@
-use crate::types::ProcessInfo;
-use std::collections::HashMap;
+use crate::container;
+use crate::port_scanner::PortScanner;
+use crate::procfs::ProcFs;
+use crate::types::{ContainerInfo, ProcessDetails, ProcessInfo};
+use nix::unistd::{sysconf, SysconfVar};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 #[allow(unused_imports)]
 use std::os::unix::fs::MetadataExt;
+use std::time::{Duration, Instant};
+
+/// How many processes read longest ago `refresh` re-reads while a wanted inode is unknown
+///
+/// The socket may be new on a process that was already cached, or belong to a
+/// process we can't inspect; either way the cost per refresh stays bounded.
+pub const STALE_FD_RESCANS: usize = 32;
+
+/// How long a looked-up container name is trusted before it is asked for again
+const CONTAINER_NAME_TTL: Duration = Duration::from_secs(60);
 
 /// Resolver for mapping socket inodes to process information
+///
+/// Meant to be kept across scans: `refresh` reads the fds of new processes only,
+/// and forgets processes that exited.
 pub struct ProcessResolver {
-    /// Cache of inode -> PID mappings
-    inode_map: HashMap<u64, u32>,
+    /// procfs tree processes are read from
+    procfs: ProcFs,
+    /// Socket fds of every process seen, keyed by PID
+    processes: HashMap<u32, ProcessFds>,
+    /// Cache of inode -> (PID, fd) holding it (several when a socket is shared across fork)
+    inode_map: HashMap<u64, Vec<(u32, u32)>>,
+    /// Number of refreshes so far
+    refreshes: u64,
+    /// Number of fd directories read so far
+    fd_scans: u64,
+    /// Container ID -> name found by the runtime, and when it was looked up
+    container_names: HashMap<String, (String, Instant)>,
+}
+
+/// Cached fd table of one process, valid while its start time is unchanged
+struct ProcessFds {
+    /// Start time in clock ticks after boot, from /proc/[pid]/stat; a reused PID differs
+    start_time: u64,
+    /// Refresh in which the fd directory was last read
+    scanned_at: u64,
+    /// fd number -> socket inode, for the fds holding a socket
+    fds: HashMap<u32, u64>,
+    /// Network namespace inode, read once when the process is first seen
+    /// (None when /proc/[pid]/ns/net can't be read: another user's process, or exited)
+    netns: Option<u64>,
 }
 
 impl ProcessResolver {
     /// Create a new resolver and build the inode map
     pub fn new() -> Self {
-        let inode_map = Self::build_inode_map();
-        Self { inode_map }
+        Self::with_procfs(ProcFs::default())
+    }
+
+    /// Create a resolver reading the given procfs tree and build the inode map
+    pub fn with_procfs(procfs: ProcFs) -> Self {
+        let mut resolver = Self {
+            procfs,
+            processes: HashMap::new(),
+            inode_map: HashMap::new(),
+            refreshes: 0,
+            fd_scans: 0,
+            container_names: HashMap::new(),
+        };
+        resolver.refresh(&HashSet::new());
+        resolver
+    }
+
+    /// Bring the inode map up to date before resolving the `wanted` socket inodes
+    ///
+    /// Exited processes are dropped, and only new or restarted ones (PID reused
+    /// with a different start time) have their fd directory read. Each wanted
+    /// inode already mapped is checked with one readlink of the fd it was seen
+    /// on, and the holder is re-read if that fd changed. A wanted inode no
+    /// process is known to hold re-reads the `STALE_FD_RESCANS` processes read
+    /// longest ago, so a socket opened by a long-running process is found within
+    /// a few refreshes.
+    pub fn refresh(&mut self, wanted: &HashSet<u64>) {
+        self.refreshes += 1;
+        let live = Self::list_pids(&self.procfs);
+        self.processes.retain(|pid, _| live.contains(pid));
+
+        let mut rescan = HashSet::new();
+        for pid in live {
+            let Some(start_time) = Self::get_start_time(&self.procfs, pid) else {
+                self.processes.remove(&pid); // Exited while we were listing
+                continue;
+            };
+
+            let known = self
+                .processes
+                .get(&pid)
+                .is_some_and(|cached| cached.start_time == start_time);
+            if !known {
+                self.processes.insert(
+                    pid,
+                    ProcessFds {
+                        start_time,
+                        scanned_at: 0,
+                        fds: HashMap::new(),
+                        netns: PortScanner::read_netns(&self.procfs, pid),
+                    },
+                );
+                rescan.insert(pid);
+            }
+        }
+
+        // A holder whose fd no longer links to the socket closed it or reused the number
+        for inode in wanted {
+            for &(pid, fd) in self.inode_map.get(inode).into_iter().flatten() {
+                let link = self.procfs.pid_path(pid, "fd").join(fd.to_string());
+                let current = fs::read_link(link)
+                    .ok()
+                    .and_then(|target| Self::parse_socket_link(&target.to_string_lossy()));
+                if current != Some(*inode) {
+                    rescan.insert(pid);
+                }
+            }
+        }
+
+        for pid in rescan {
+            self.scan_process(pid);
+        }
+        self.rebuild_inode_map();
+
+        let missing = wanted
+            .iter()
+            .any(|inode| !self.inode_map.contains_key(inode));
+        if missing {
+            let mut stale: Vec<(u64, u32)> = self
+                .processes
+                .iter()
+                .filter(|(_, cached)| cached.scanned_at < self.refreshes)
+                .map(|(&pid, cached)| (cached.scanned_at, pid))
+                .collect();
+            stale.sort_unstable();
+
+            for (_, pid) in stale.into_iter().take(STALE_FD_RESCANS) {
+                self.scan_process(pid);
+            }
+            self.rebuild_inode_map();
+        }
+    }
+
+    /// Number of fd directories read since the resolver was created
+    pub fn fd_scans(&self) -> u64 {
+        self.fd_scans
+    }
+
+    /// PIDs seen by the last refresh, grouped by network namespace inode
+    ///
+    /// Comes from the cache, so each process's namespace is read only once.
+    /// Processes whose namespace couldn't be read are left out.
+    pub fn pids_by_netns(&self) -> HashMap<u64, Vec<u32>> {
+        let mut groups: HashMap<u64, Vec<u32>> = HashMap::new();
+        for (&pid, cached) in &self.processes {
+            if let Some(netns) = cached.netns {
+                groups.entry(netns).or_default().push(pid);
+            }
+        }
+        for pids in groups.values_mut() {
+            pids.sort_unstable();
+        }
+        groups
     }
 
     /// Resolve an inode to process information
+    ///
+    /// When several processes share the socket, this is the master (see `resolve_all`).
     pub fn resolve(&self, inode: u64) -> Option<ProcessInfo> {
-        let pid = *self.inode_map.get(&inode)?;
-        Self::get_process_info(pid)
+        self.resolve_all(inode).into_iter().next()
+    }
+
+    /// Resolve an inode to every process holding it, master first
+    ///
+    /// Pre-fork servers (nginx, gunicorn) share their listening socket between a
+    /// master and its workers. The master is the owner whose parent doesn't hold
+    /// the socket too; the lowest PID wins if several owners are unrelated.
+    pub fn resolve_all(&self, inode: u64) -> Vec<ProcessInfo> {
+        let Some(holders) = self.inode_map.get(&inode) else {
+            return Vec::new();
+        };
+
+        let mut owners: Vec<ProcessInfo> = holders
+            .iter()
+            .filter_map(|&(pid, _)| {
+                let mut info = self.get_process_info(pid)?;
+                info.container = self.get_container(pid);
+                Some(info)
+            })
+            .collect();
+
+        let holders: HashSet<u32> = owners.iter().map(|o| o.pid).collect();
+        owners.sort_by_key(|o| (holders.contains(&o.ppid), o.pid));
+
+        owners
+    }
+
+    /// Detect the container a process runs in from /proc/[pid]/cgroup
+    ///
+    /// The name comes from the cache only; runtime sockets are never queried here,
+    /// so resolving stays cheap while callers hold the resolver.
+    fn get_container(&self, pid: u32) -> Option<ContainerInfo> {
+        let cgroup = fs::read_to_string(self.procfs.pid_path(pid, "cgroup")).ok()?;
+        let (runtime, id) = container::parse_cgroup(&cgroup)?;
+        let name = self.container_name(&id);
+
+        Some(ContainerInfo { runtime, id, name })
+    }
+
+    /// Cached name of a container, if it was looked up recently
+    pub fn container_name(&self, id: &str) -> Option<String> {
+        self.container_names
+            .get(id)
+            .filter(|(_, looked_up)| looked_up.elapsed() < CONTAINER_NAME_TTL)
+            .map(|(name, _)| name.clone())
+    }
+
+    /// Record container names found by the runtime and forget containers no longer seen
+    ///
+    /// `seen` holds the IDs of every container in the current scan. Only successful
+    /// lookups are passed in `found`, so an unreachable runtime is asked again next time.
+    pub fn update_container_names(
+        &mut self,
+        seen: &HashSet<String>,
+        found: impl IntoIterator<Item = (String, String)>,
+    ) {
+        let now = Instant::now();
+        for (id, name) in found {
+            self.container_names.insert(id, (name, now));
+        }
+        self.container_names.retain(|id, _| seen.contains(id));
     }
 
-    /// Build a mapping of socket inodes to PIDs by scanning /proc/[pid]/fd/
-    fn build_inode_map() -> HashMap<u64, u32> {
-        let mut map = HashMap::new();
+    /// List the PIDs under the proc root
+    pub(crate) fn list_pids(procfs: &ProcFs) -> HashSet<u32> {
+        let mut pids = HashSet::new();
 
         // Read /proc directory
-        let proc_dir = match fs::read_dir("/proc") {
+        let proc_dir = match fs::read_dir(procfs.root()) {
             Ok(dir) => dir,
-            Err(_) => return map,
+            Err(_) => return pids,
         };
 
         for entry in proc_dir.flatten() {
-            let path = entry.path();
-
             // Only process numeric directories (PIDs)
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if let Ok(pid) = name.parse::<u32>() {
-                    Self::scan_process_fds(pid, &mut map);
+            if let Some(pid) = entry.file_name().to_str().and_then(|n| n.parse().ok()) {
+                pids.insert(pid);
+            }
+        }
+
+        pids
+    }
+
+    /// Read a process's start time (field 22) from /proc/[pid]/stat
+    fn get_start_time(procfs: &ProcFs, pid: u32) -> Option<u64> {
+        let stat = fs::read_to_string(procfs.pid_path(pid, "stat")).ok()?;
+        Self::get_stat_field(&stat, 22)
+    }
+
+    /// Read a process's arguments from /proc/[pid]/cmdline (empty for kernel threads)
+    pub(crate) fn get_cmdline(procfs: &ProcFs, pid: u32) -> Vec<String> {
+        // Arguments are NUL-separated, with a trailing NUL
+        fs::read(procfs.pid_path(pid, "cmdline"))
+            .map(|raw| {
+                raw.split(|&b| b == 0)
+                    .filter(|arg| !arg.is_empty())
+                    .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Read a process's environment from /proc/[pid]/environ
+    ///
+    /// None if unreadable, which it is for other users' processes.
+    pub(crate) fn get_environ(procfs: &ProcFs, pid: u32) -> Option<BTreeMap<String, String>> {
+        let raw = fs::read(procfs.pid_path(pid, "environ")).ok()?;
+
+        // NUL-separated KEY=VALUE entries
+        Some(
+            raw.split(|&b| b == 0)
+                .filter_map(|entry| {
+                    let entry = String::from_utf8_lossy(entry);
+                    let (key, value) = entry.split_once('=')?;
+                    Some((key.to_string(), value.to_string()))
+                })
+                .collect(),
+        )
+    }
+
+    /// Extract a numeric field of /proc/[pid]/stat, numbered from 1 as in proc(5)
+    pub(crate) fn get_stat_field(stat: &str, field: usize) -> Option<u64> {
+        // The name in parentheses may itself contain spaces or ')'
+        let after_name = &stat[stat.rfind(')')? + 1..];
+
+        // Fields after the name start at field 3 (state)
+        after_name
+            .split_whitespace()
+            .nth(field.checked_sub(3)?)?
+            .parse()
+            .ok()
+    }
+
+    /// Rebuild the inode -> (PID, fd) map from the cached fd tables
+    fn rebuild_inode_map(&mut self) {
+        self.inode_map.clear();
+
+        for (&pid, cached) in &self.processes {
+            for (&fd, &inode) in &cached.fds {
+                // A process may hold the same socket on several fds
+                let holders = self.inode_map.entry(inode).or_default();
+                if !holders.iter().any(|&(holder, _)| holder == pid) {
+                    holders.push((pid, fd));
                 }
             }
         }
+    }
 
-        map
+    /// Read a cached process's fd directory again, replacing its fd table
+    fn scan_process(&mut self, pid: u32) {
+        let Some(cached) = self.processes.get_mut(&pid) else {
+            return;
+        };
+        cached.fds = Self::scan_process_fds(&self.procfs, pid);
+        cached.scanned_at = self.refreshes;
+        self.fd_scans += 1;
     }
 
     /// Scan a process's file descriptors for socket inodes
-    fn scan_process_fds(pid: u32, map: &mut HashMap<u64, u32>) {
-        let fd_path = format!("/proc/{}/fd", pid);
-        let fd_dir = match fs::read_dir(&fd_path) {
-            Ok(dir) => dir,
-            Err(_) => return, // Permission denied or process exited
+    ///
+    /// Empty when the fd directory can't be read (another user's process, or exited).
+    fn scan_process_fds(procfs: &ProcFs, pid: u32) -> HashMap<u32, u64> {
+        let fd_path = procfs.pid_path(pid, "fd");
+        let Ok(fd_dir) = fs::read_dir(&fd_path) else {
+            return HashMap::new();
         };
 
+        let mut fds = HashMap::new();
+
         for entry in fd_dir.flatten() {
-            let link_path = entry.path();
+            let Some(fd) = entry
+                .file_name()
+                .to_str()
+                .and_then(|n| n.parse::<u32>().ok())
+            else {
+                continue;
+            };
 
             // Read the symlink target
-            if let Ok(target) = fs::read_link(&link_path) {
-                let target_str = target.to_string_lossy();
-
-                // Check if it's a socket: socket:[inode]
-                if target_str.starts_with("socket:[") && target_str.ends_with(']') {
-                    if let Some(inode_str) = target_str
-                        .strip_prefix("socket:[")
-                        .and_then(|s| s.strip_suffix(']'))
-                    {
-                        if let Ok(inode) = inode_str.parse::<u64>() {
-                            map.insert(inode, pid);
-                        }
-                    }
-                }
+            let inode = fs::read_link(entry.path())
+                .ok()
+                .and_then(|target| Self::parse_socket_link(&target.to_string_lossy()));
+            if let Some(inode) = inode {
+                fds.insert(fd, inode);
             }
         }
+
+        fds
+    }
+
+    /// Parse a socket fd link target like "socket:[12345]"
+    fn parse_socket_link(target: &str) -> Option<u64> {
+        target
+            .strip_prefix("socket:[")
+            .and_then(|s| s.strip_suffix(']'))
+            .and_then(|s| s.parse().ok())
     }
 
     /// Get process information from /proc/[pid]/
-    fn get_process_info(pid: u32) -> Option<ProcessInfo> {
-        let comm_path = format!("/proc/{}/comm", pid);
-        let status_path = format!("/proc/{}/status", pid);
+    fn get_process_info(&self, pid: u32) -> Option<ProcessInfo> {
+        let comm_path = self.procfs.pid_path(pid, "comm");
+        let status_path = self.procfs.pid_path(pid, "status");
 
         // Read process name from /proc/[pid]/comm
         let name = fs::read_to_string(&comm_path)
@@ -88,26 +382,139 @@ impl ProcessResolver {
             .trim()
             .to_string();
 
-        // Read UID from /proc/[pid]/status
-        let uid = Self::get_process_uid(&status_path)?;
+        // Read UID and parent PID from /proc/[pid]/status
+        let status = fs::read_to_string(&status_path).ok()?;
+        let uid = Self::get_status_field(&status, "Uid:")?;
+        let ppid = Self::get_status_field(&status, "PPid:").unwrap_or(0);
 
         // Get username from UID
         let user = Self::get_username(uid);
 
-        Some(ProcessInfo { pid, name, user })
+        let start_time = Self::get_start_time(&self.procfs, pid);
+
+        Some(ProcessInfo {
+            pid,
+            ppid,
+            name,
+            user,
+            start_time,
+            container: None,
+        })
     }
 
-    /// Extract UID from /proc/[pid]/status
-    fn get_process_uid(status_path: &str) -> Option<u32> {
-        let content = fs::read_to_string(status_path).ok()?;
+    /// Resolve a process and its ancestors via the PPid chain, nearest first
+    ///
+    /// e.g. node -> npm -> bash -> systemd. The chain stops at the first
+    /// ancestor that can't be read (or is outside our PID namespace).
+    pub fn get_ancestry(&self, pid: u32) -> Vec<ProcessInfo> {
+        let mut chain = Vec::new();
+        let mut seen = HashSet::new();
+        let mut current = pid;
 
-        for line in content.lines() {
-            if line.starts_with("Uid:") {
-                // Format: Uid: real effective saved filesystem
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    return parts[1].parse().ok();
-                }
+        // PPid 0 marks the top of the tree; `seen` guards against a pid reused mid-walk
+        while current != 0 && seen.insert(current) {
+            let Some(info) = self.get_process_info(current) else {
+                break;
+            };
+            current = info.ppid;
+            chain.push(info);
+        }
+
+        chain
+    }
+
+    /// Get detailed information about a process from /proc/[pid]/
+    ///
+    /// Returns None if the process doesn't exist. Fields that need access to
+    /// another user's process (exe, cwd, fds) are None when unreadable.
+    pub fn get_process_details(&self, pid: u32) -> Option<ProcessDetails> {
+        let info = self.get_process_info(pid)?;
+        let status = fs::read_to_string(self.procfs.pid_path(pid, "status")).ok()?;
+        let stat = fs::read_to_string(self.procfs.pid_path(pid, "stat")).ok()?;
+
+        let cmdline = Self::get_cmdline(&self.procfs, pid);
+
+        let read_link = |name: &str| {
+            fs::read_link(self.procfs.pid_path(pid, name))
+                .ok()
+                .map(|target| target.to_string_lossy().into_owned())
+        };
+
+        let fd_count = fs::read_dir(self.procfs.pid_path(pid, "fd"))
+            .ok()
+            .map(|dir| dir.count());
+
+        // utime, stime and starttime are in clock ticks
+        let ticks_per_sec = Self::clock_ticks_per_sec();
+        let cpu_ticks = Self::get_stat_field(&stat, 14).unwrap_or(0)
+            + Self::get_stat_field(&stat, 15).unwrap_or(0);
+        let start_time = Self::get_stat_field(&stat, 22)
+            .zip(self.get_boot_time())
+            .map(|(ticks, boot)| boot + ticks / ticks_per_sec);
+
+        Some(ProcessDetails {
+            pid,
+            ppid: info.ppid,
+            name: info.name,
+            cmdline,
+            exe: read_link("exe"),
+            cwd: read_link("cwd"),
+            start_time,
+            threads: Self::get_status_field(&status, "Threads:").unwrap_or(0),
+            // Kernel threads have no VmRSS line
+            rss_bytes: Self::get_status_field(&status, "VmRSS:").unwrap_or(0) as u64 * 1024,
+            cpu_time_ms: cpu_ticks * 1000 / ticks_per_sec,
+            fd_count,
+            tty: Self::get_stat_field(&stat, 7).and_then(Self::tty_name),
+            user: info.user,
+        })
+    }
+
+    /// System boot time in seconds since the epoch, from the btime line of /proc/stat
+    fn get_boot_time(&self) -> Option<u64> {
+        let stat = fs::read_to_string(self.procfs.root().join("stat")).ok()?;
+        stat.lines()
+            .find_map(|line| line.strip_prefix("btime "))
+            .and_then(|value| value.trim().parse().ok())
+    }
+
+    /// Kernel clock ticks per second (USER_HZ), the unit of /proc/[pid]/stat times
+    fn clock_ticks_per_sec() -> u64 {
+        sysconf(SysconfVar::CLK_TCK)
+            .ok()
+            .flatten()
+            .filter(|&ticks| ticks > 0)
+            .map_or(100, |ticks| ticks as u64)
+    }
+
+    /// Name the terminal device encoded in the tty_nr field of /proc/[pid]/stat
+    ///
+    /// Returns None for processes without a controlling terminal.
+    fn tty_name(tty_nr: u64) -> Option<String> {
+        if tty_nr == 0 {
+            return None;
+        }
+
+        // Same encoding as the kernel's new_encode_dev()
+        let major = (tty_nr >> 8) & 0xfff;
+        let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+
+        Some(match major {
+            // Unix98 pseudo-terminals span majors 136-143
+            136..=143 => format!("/dev/pts/{}", (major - 136) * 256 + minor),
+            4 if minor < 64 => format!("/dev/tty{}", minor),
+            4 => format!("/dev/ttyS{}", minor - 64),
+            _ => format!("{}:{}", major, minor),
+        })
+    }
+
+    /// Extract the first number of a /proc/[pid]/status field
+    ///
+    /// Format: "Uid:   real effective saved filesystem", "PPid:  1"
+    pub(crate) fn get_status_field(status: &str, key: &str) -> Option<u32> {
+        for line in status.lines() {
+            if let Some(values) = line.strip_prefix(key) {
+                return values.split_whitespace().next()?.parse().ok();
             }
         }
 
@@ -115,7 +522,7 @@ impl ProcessResolver {
     }
 
     /// Get username from UID using the users crate
-    fn get_username(uid: u32) -> String {
+    pub fn get_username(uid: u32) -> String {
         users::get_user_by_uid(uid)
             .map(|u| u.name().to_string_lossy().to_string())
             .unwrap_or_else(|| uid.to_string())
@@ -123,8 +530,8 @@ impl ProcessResolver {
 
     /// Alternative: get process owner UID from file metadata
     #[allow(dead_code)]
-    fn get_process_uid_from_metadata(pid: u32) -> Option<u32> {
-        let path = format!("/proc/{}", pid);
+    fn get_process_uid_from_metadata(&self, pid: u32) -> Option<u32> {
+        let path = self.procfs.pid_dir(pid);
         fs::metadata(&path).ok().map(|m| m.uid())
     }
 }
@@ -138,6 +545,7 @@ impl Default for ProcessResolver {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::os::fd::AsRawFd;
 
     #[test]
     fn test_resolver_creation() {
@@ -147,6 +555,102 @@ mod tests {
         assert!(resolver.inode_map.len() >= 0); // May be empty in test environment
     }
 
+    #[test]
+    fn test_parse_socket_link() {
+        assert_eq!(ProcessResolver::parse_socket_link("socket:[12345]"), Some(12345));
+        assert_eq!(ProcessResolver::parse_socket_link("pipe:[12345]"), None);
+        assert_eq!(ProcessResolver::parse_socket_link("/dev/null"), None);
+    }
+
+    #[test]
+    fn test_get_start_time_self() {
+        let procfs = ProcFs::default();
+        let pid = std::process::id();
+
+        let first = ProcessResolver::get_start_time(&procfs, pid);
+        assert!(first.is_some());
+        assert_eq!(ProcessResolver::get_start_time(&procfs, pid), first);
+    }
+
+    #[test]
+    fn test_refresh_finds_new_socket() {
+        let mut resolver = ProcessResolver::new();
+
+        // Opened after the resolver's initial scan
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let link = std::fs::read_link(format!("/proc/self/fd/{}", listener.as_raw_fd())).unwrap();
+        let inode = ProcessResolver::parse_socket_link(&link.to_string_lossy()).unwrap();
+
+        // Cached processes are re-read a batch at a time, oldest first
+        let wanted = HashSet::from([inode]);
+        let batches = resolver.processes.len() / STALE_FD_RESCANS + 1;
+        for _ in 0..batches {
+            resolver.refresh(&wanted);
+        }
+        assert!(resolver
+            .resolve_all(inode)
+            .iter()
+            .any(|o| o.pid == std::process::id()));
+        drop(listener);
+    }
+
+    #[test]
+    fn test_get_stat_field_with_odd_name() {
+        // comm may contain spaces and parentheses
+        let stat = "42 (my (odd) name) S 1 42 42 34816 42 4194304 100 0 0 0 250 50 0 0 20 0 3 0 123456 0 0";
+        assert_eq!(ProcessResolver::get_stat_field(stat, 4), Some(1));
+        assert_eq!(ProcessResolver::get_stat_field(stat, 7), Some(34816));
+        assert_eq!(ProcessResolver::get_stat_field(stat, 14), Some(250));
+        assert_eq!(ProcessResolver::get_stat_field(stat, 22), Some(123456));
+    }
+
+    #[test]
+    fn test_tty_name() {
+        assert_eq!(ProcessResolver::tty_name(0), None);
+        // major 136, minor 3
+        assert_eq!(ProcessResolver::tty_name(34816 + 3).as_deref(), Some("/dev/pts/3"));
+        // major 4, minor 1
+        assert_eq!(ProcessResolver::tty_name(1025).as_deref(), Some("/dev/tty1"));
+        // major 4, minor 64
+        assert_eq!(ProcessResolver::tty_name(1088).as_deref(), Some("/dev/ttyS0"));
+    }
+
+    #[test]
+    fn test_get_process_details_self() {
+        let resolver = ProcessResolver::new();
+        let details = resolver.get_process_details(std::process::id()).unwrap();
+
+        assert_eq!(details.pid, std::process::id());
+        assert!(!details.cmdline.is_empty());
+        assert_eq!(
+            details.exe.map(std::path::PathBuf::from),
+            std::env::current_exe().ok()
+        );
+        assert!(details.threads >= 1);
+        assert!(details.rss_bytes > 0);
+        assert!(details.fd_count.unwrap() >= 3);
+        assert!(details.start_time.is_some());
+    }
+
+    #[test]
+    fn test_get_ancestry_self() {
+        let resolver = ProcessResolver::new();
+        let chain = resolver.get_ancestry(std::process::id());
+
+        assert_eq!(chain[0].pid, std::process::id());
+        for pair in chain.windows(2) {
+            assert_eq!(pair[0].ppid, pair[1].pid);
+        }
+    }
+
+    #[test]
+    fn test_get_status_field() {
+        let status = "Name:\tnginx\nPid:\t1201\nPPid:\t1200\nUid:\t33\t33\t33\t33\n";
+        assert_eq!(ProcessResolver::get_status_field(status, "PPid:"), Some(1200));
+        assert_eq!(ProcessResolver::get_status_field(status, "Uid:"), Some(33));
+        assert_eq!(ProcessResolver::get_status_field(status, "Gid:"), None);
+    }
+
     #[test]
     fn test_get_username() {
         // UID 0 should be root on most systems
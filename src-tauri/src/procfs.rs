@@ -0,0 +1,91 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding where procfs is mounted (e.g. "/host/proc")
+pub const PROC_ROOT_ENV: &str = "PORTWATCHER_PROC_ROOT";
+
+/// The system's own procfs mount
+const DEFAULT_PROC_ROOT: &str = "/proc";
+
+/// Location of the procfs tree that scanning, resolution and process checks read from
+///
+/// Defaults to /proc. Point it at a host /proc bind-mounted into a container, or at
+/// a fixture directory laid out like /proc in tests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcFs {
+    root: PathBuf,
+}
+
+impl ProcFs {
+    /// Use the procfs tree rooted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Use `PORTWATCHER_PROC_ROOT` if set, /proc otherwise
+    pub fn from_env() -> Self {
+        match std::env::var_os(PROC_ROOT_ENV) {
+            Some(root) if !root.is_empty() => Self::new(root),
+            _ => Self::default(),
+        }
+    }
+
+    /// Root directory of the tree (e.g. "/proc")
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether this is the system's own /proc
+    ///
+    /// Netlink answers for the kernel we run on, so it only agrees with the
+    /// tables under the root when the root is our own /proc.
+    pub fn is_system(&self) -> bool {
+        self.root == Path::new(DEFAULT_PROC_ROOT)
+    }
+
+    /// The caller's socket tables (<root>/net)
+    pub fn net_dir(&self) -> PathBuf {
+        self.root.join("net")
+    }
+
+    /// A process's directory (<root>/<pid>); `pid` may also be "self"
+    pub fn pid_dir(&self, pid: impl Display) -> PathBuf {
+        self.root.join(pid.to_string())
+    }
+
+    /// A file inside a process's directory (e.g. <root>/<pid>/status)
+    pub fn pid_path(&self, pid: impl Display, name: &str) -> PathBuf {
+        self.pid_dir(pid).join(name)
+    }
+}
+
+impl Default for ProcFs {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROC_ROOT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_system_proc() {
+        let proc_fs = ProcFs::default();
+        assert_eq!(proc_fs.root(), Path::new("/proc"));
+        assert!(proc_fs.is_system());
+        assert_eq!(proc_fs.net_dir(), PathBuf::from("/proc/net"));
+    }
+
+    #[test]
+    fn test_custom_root_paths() {
+        let proc_fs = ProcFs::new("/host/proc");
+        assert!(!proc_fs.is_system());
+        assert_eq!(proc_fs.net_dir(), PathBuf::from("/host/proc/net"));
+        assert_eq!(proc_fs.pid_dir(42), PathBuf::from("/host/proc/42"));
+        assert_eq!(
+            proc_fs.pid_path("self", "ns/net"),
+            PathBuf::from("/host/proc/self/ns/net")
+        );
+    }
+}
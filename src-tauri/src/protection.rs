@@ -0,0 +1,167 @@
+use crate::config::Config;
+use crate::process_resolver::ProcessResolver;
+use crate::procfs::ProcFs;
+use crate::types::ProtectionReason;
+use std::fs;
+use std::path::Path;
+
+/// Display servers and compositors; killing one takes the whole desktop down
+const DISPLAY_SERVERS: &[&str] = &[
+    "Xorg",
+    "Xwayland",
+    "X",
+    "gnome-shell",
+    "kwin_wayland",
+    "kwin_x11",
+    "plasmashell",
+    "mutter",
+    "weston",
+    "sway",
+    "Hyprland",
+    "labwc",
+    "wayfire",
+    "gdm",
+    "gdm3",
+    "sddm",
+    "lightdm",
+];
+
+/// Services whose loss locks the user out of the machine or breaks the session
+const SYSTEM_SERVICES: &[&str] = &["systemd", "sshd", "dbus-daemon", "dbus-broker", "polkitd"];
+
+/// Processes PortWatcher refuses to signal, whatever the user clicks
+///
+/// Always covers PID 1, PortWatcher itself, the leader of its session, display
+/// servers and a few system services; more process names can be added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProtectionPolicy {
+    /// Extra process names, e.g. from the user's config
+    names: Vec<String>,
+}
+
+impl ProtectionPolicy {
+    /// Only the built-in protections
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Built-in protections plus the `protected_processes` of the user's config
+    pub fn from_config() -> Self {
+        Self::new().with_names(Config::load().protected_processes)
+    }
+
+    /// Also protect processes with these names
+    pub fn with_names(mut self, names: impl IntoIterator<Item = String>) -> Self {
+        self.names.extend(names);
+        self
+    }
+
+    /// Process names protected on top of the built-in protections
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Why `pid` must not be signalled, None if it may be
+    ///
+    /// PortWatcher's own PID and session are looked up through `procfs`, so they
+    /// are right even when it points at another PID namespace's /proc.
+    pub fn check(&self, procfs: &ProcFs, pid: u32) -> Option<ProtectionReason> {
+        if pid == 1 {
+            return Some(ProtectionReason::Init);
+        }
+
+        if let Some(own_pid) = Self::own_pid(procfs) {
+            if pid == own_pid {
+                return Some(ProtectionReason::PortWatcher);
+            }
+            if Self::session(procfs, own_pid) == Some(pid) {
+                return Some(ProtectionReason::SessionLeader);
+            }
+        }
+
+        let names = Self::names_of(procfs, pid);
+        let is_named = |list: &[&str]| names.iter().any(|name| list.contains(&name.as_str()));
+        if is_named(DISPLAY_SERVERS) {
+            Some(ProtectionReason::DisplayServer)
+        } else if is_named(SYSTEM_SERVICES) {
+            Some(ProtectionReason::SystemService)
+        } else if names.iter().any(|name| self.names.contains(name)) {
+            Some(ProtectionReason::Configured)
+        } else {
+            None
+        }
+    }
+
+    /// Our PID as seen from `procfs` (the target of its "self" link)
+    fn own_pid(procfs: &ProcFs) -> Option<u32> {
+        let target = fs::read_link(procfs.pid_dir("self")).ok()?;
+        target.to_str()?.parse().ok()
+    }
+
+    /// Session ID of a process (field 6 of /proc/[pid]/stat), the PID of its leader
+    fn session(procfs: &ProcFs, pid: u32) -> Option<u32> {
+        let stat = fs::read_to_string(procfs.pid_path(pid, "stat")).ok()?;
+        ProcessResolver::get_stat_field(&stat, 6).and_then(|sid| u32::try_from(sid).ok())
+    }
+
+    /// Names a process goes by: its comm, and the file name of its argv[0]
+    ///
+    /// comm is cut to 15 characters, so longer names only match the latter.
+    fn names_of(procfs: &ProcFs, pid: u32) -> Vec<String> {
+        let mut names = Vec::new();
+        if let Ok(comm) = fs::read_to_string(procfs.pid_path(pid, "comm")) {
+            names.push(comm.trim_end().to_string());
+        }
+        if let Ok(cmdline) = fs::read(procfs.pid_path(pid, "cmdline")) {
+            let argv0 = cmdline.split(|&b| b == 0).next().unwrap_or_default();
+            if let Some(name) = Path::new(&*String::from_utf8_lossy(argv0)).file_name() {
+                names.push(name.to_string_lossy().into_owned());
+            }
+        }
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn test_builtin_protections() {
+        let policy = ProtectionPolicy::new();
+        let procfs = ProcFs::default();
+        let own_pid = std::process::id();
+
+        assert_eq!(policy.check(&procfs, 1), Some(ProtectionReason::Init));
+        assert_eq!(
+            policy.check(&procfs, own_pid),
+            Some(ProtectionReason::PortWatcher)
+        );
+
+        let sid = ProtectionPolicy::session(&procfs, own_pid).unwrap();
+        if ![0, 1, own_pid].contains(&sid) {
+            assert_eq!(
+                policy.check(&procfs, sid),
+                Some(ProtectionReason::SessionLeader)
+            );
+        }
+    }
+
+    #[test]
+    fn test_configured_names() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let procfs = ProcFs::default();
+
+        assert_eq!(ProtectionPolicy::new().check(&procfs, child.id()), None);
+        assert_eq!(
+            ProtectionPolicy::new()
+                .with_names(["sleep".to_string()])
+                .check(&procfs, child.id()),
+            Some(ProtectionReason::Configured)
+        );
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+use crate::types::{KillRecord, KillResult, RelaunchError};
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Start a killed process again from its kill history record, returning the new PID
+///
+/// The command line runs detached, in its own process group, from the recorded
+/// working directory and with exactly the recorded environment. Only records
+/// of processes known to be gone are relaunched, so no second copy is started.
+pub fn relaunch(record: &KillRecord) -> Result<u32, RelaunchError> {
+    if !matches!(
+        record.outcome,
+        KillResult::Success { .. } | KillResult::ProcessNotFound { .. }
+    ) {
+        return Err(RelaunchError::NotStopped(record.pid));
+    }
+
+    let (program, args) = record
+        .cmdline
+        .split_first()
+        .ok_or(RelaunchError::NotRecorded("command line", record.pid))?;
+    let cwd = record
+        .cwd
+        .as_deref()
+        .ok_or(RelaunchError::NotRecorded("working directory", record.pid))?;
+    let environ = record
+        .environ
+        .as_ref()
+        .ok_or(RelaunchError::NotRecorded("environment", record.pid))?;
+
+    // "./server" meant relative to the process's directory; bare names go through its PATH
+    let program_path = if program.contains('/') {
+        Path::new(cwd).join(program)
+    } else {
+        program.into()
+    };
+
+    let mut child = Command::new(program_path)
+        .args(args)
+        .current_dir(cwd)
+        .env_clear()
+        .envs(environ)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        // Out of PortWatcher's group, so signals meant for it don't reach the server
+        .process_group(0)
+        .spawn()
+        .map_err(|source| RelaunchError::SpawnError {
+            program: program.clone(),
+            source,
+        })?;
+
+    let pid = child.id();
+    // Reap it whenever it exits, so it doesn't linger as a zombie
+    thread::spawn(move || child.wait());
+    Ok(pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{KillStage, ProcessSignal};
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::time::{Duration, Instant};
+
+    fn record(cmdline: &[&str], cwd: &Path) -> KillRecord {
+        KillRecord {
+            id: 1,
+            timestamp: 0,
+            pid: 4242,
+            name: Some("sh".to_string()),
+            cmdline: cmdline.iter().map(|arg| arg.to_string()).collect(),
+            cwd: Some(cwd.to_string_lossy().into_owned()),
+            environ: Some(BTreeMap::from([
+                ("PATH".to_string(), "/usr/bin:/bin".to_string()),
+                ("GREETING".to_string(), "hello".to_string()),
+            ])),
+            port: None,
+            signal: ProcessSignal::Sigterm,
+            elevated: false,
+            outcome: KillResult::Success {
+                stage: KillStage::Sigterm,
+            },
+        }
+    }
+
+    #[test]
+    fn test_relaunch_restores_cwd_and_environment() {
+        let dir = std::env::temp_dir().join(format!("portwatcher-relaunch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let script = r#"echo "$GREETING $HOME" > out"#;
+        relaunch(&record(&["sh", "-c", script], &dir)).unwrap();
+
+        // Only the recorded variables are set, so HOME is empty
+        let out = dir.join("out");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while fs::read_to_string(&out).map_or(true, |s| !s.ends_with('\n')) {
+            assert!(Instant::now() < deadline, "relaunched process never wrote");
+            thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(fs::read_to_string(&out).unwrap(), "hello \n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_relaunch_needs_recorded_state() {
+        let mut unreadable = record(&["sh"], Path::new("/"));
+        unreadable.environ = None;
+        assert!(matches!(
+            relaunch(&unreadable),
+            Err(RelaunchError::NotRecorded("environment", 4242))
+        ));
+
+        assert!(matches!(
+            relaunch(&record(&[], Path::new("/"))),
+            Err(RelaunchError::NotRecorded("command line", 4242))
+        ));
+        assert!(matches!(
+            relaunch(&record(&["./missing-server"], Path::new("/"))),
+            Err(RelaunchError::SpawnError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_relaunch_needs_process_gone() {
+        for outcome in [
+            KillResult::Signalled {
+                signal: ProcessSignal::Sighup,
+            },
+            KillResult::PermissionDenied { pid: 4242 },
+            KillResult::IdentityMismatch { pid: 4242 },
+            KillResult::Error {
+                message: "Process 4242 is still running after SIGTERM".to_string(),
+            },
+        ] {
+            let mut survivor = record(&["./missing-server"], Path::new("/"));
+            survivor.outcome = outcome;
+            assert!(matches!(
+                relaunch(&survivor),
+                Err(RelaunchError::NotStopped(4242))
+            ));
+        }
+    }
+}
@@ -0,0 +1,472 @@
+use crate::types::{
+    AddressFamily, PortRange, Protocol, RawSocketEntry, ScanError, ScanMode, TcpState,
+};
+use nix::libc;
+use nix::sys::socket::{self, MsgFlags, NetlinkAddr, SockFlag, SockProtocol, SockType};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::os::fd::AsRawFd;
+
+/// Netlink message type for sock_diag requests (linux/sock_diag.h)
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+
+/// Request attribute carrying an inet_diag filter program (linux/inet_diag.h)
+const INET_DIAG_REQ_BYTECODE: u16 = 1;
+
+/// Filter opcodes: source port >= / <= operand
+const INET_DIAG_BC_S_GE: u8 = 2;
+const INET_DIAG_BC_S_LE: u8 = 3;
+
+/// Size of struct nlmsghdr
+const NLMSG_HDR_LEN: usize = 16;
+
+/// Size of struct inet_diag_req_v2
+const DIAG_REQ_LEN: usize = 56;
+
+/// Size of struct inet_diag_msg
+const DIAG_MSG_LEN: usize = 72;
+
+/// Size of a filter instruction (struct inet_diag_bc_op)
+const BC_OP_LEN: usize = 4;
+
+/// Receive buffer size, large enough for a batch of dump messages
+const RECV_BUF_LEN: usize = 64 * 1024;
+
+/// Bitmask selecting every TCP state in `idiag_states`
+const ALL_STATES: u32 = 0xFFFF_FFFF;
+
+/// Client for the kernel's INET_DIAG socket dump interface
+///
+/// Returns the same entries as parsing /proc/net/{tcp,udp,udplite}{,6}, but asks the kernel
+/// to filter by state and port range instead of formatting every socket as text.
+pub struct SockDiag;
+
+impl SockDiag {
+    /// Dump one socket table (protocol + address family) via netlink
+    ///
+    /// Fails with `ScanError::NetlinkError` when sock_diag is unavailable (e.g. the
+    /// inet_diag module isn't loaded or netlink sockets are blocked), so callers
+    /// can fall back to /proc.
+    pub fn query(
+        protocol: Protocol,
+        family: AddressFamily,
+        mode: ScanMode,
+        range: Option<&PortRange>,
+    ) -> Result<Vec<RawSocketEntry>, ScanError> {
+        let ip_protocol = Self::ip_protocol(protocol).ok_or_else(|| {
+            ScanError::NetlinkError(format!("{} sockets are not supported", protocol))
+        })?;
+
+        // Only TCP has a LISTEN state; every state is requested for other protocols
+        let states = match (protocol, mode) {
+            (Protocol::Tcp, ScanMode::Listening) => 1 << TcpState::Listen.code(),
+            _ => ALL_STATES,
+        };
+
+        let request = Self::build_request(ip_protocol, family, states, range);
+
+        let fd = socket::socket(
+            socket::AddressFamily::Netlink,
+            SockType::Datagram,
+            SockFlag::SOCK_CLOEXEC,
+            SockProtocol::NetlinkSockDiag,
+        )
+        .map_err(|e| ScanError::NetlinkError(format!("Failed to open socket: {}", e)))?;
+
+        socket::sendto(
+            fd.as_raw_fd(),
+            &request,
+            &NetlinkAddr::new(0, 0),
+            MsgFlags::empty(),
+        )
+        .map_err(|e| ScanError::NetlinkError(format!("Failed to send request: {}", e)))?;
+
+        let mut entries = Vec::new();
+        let mut buf = vec![0u8; RECV_BUF_LEN];
+
+        loop {
+            let len = socket::recv(fd.as_raw_fd(), &mut buf, MsgFlags::empty())
+                .map_err(|e| ScanError::NetlinkError(format!("Failed to receive: {}", e)))?;
+
+            if len == 0 {
+                return Err(ScanError::NetlinkError(
+                    "Connection closed before end of dump".to_string(),
+                ));
+            }
+
+            if Self::parse_messages(&buf[..len], protocol, &mut entries)? {
+                return Ok(entries);
+            }
+        }
+    }
+
+    /// Whether sockets of this protocol can be dumped through INET_DIAG
+    ///
+    /// Raw, ICMP and SCTP sockets have no (or only optionally loaded) diag
+    /// handlers, so they are always read from /proc.
+    pub fn supports(protocol: Protocol) -> bool {
+        Self::ip_protocol(protocol).is_some()
+    }
+
+    /// IP protocol number to request for a protocol
+    fn ip_protocol(protocol: Protocol) -> Option<u8> {
+        match protocol {
+            Protocol::Tcp => Some(libc::IPPROTO_TCP as u8),
+            Protocol::Udp => Some(libc::IPPROTO_UDP as u8),
+            Protocol::UdpLite => Some(libc::IPPROTO_UDPLITE as u8),
+            Protocol::Raw | Protocol::Icmp | Protocol::Sctp => None,
+        }
+    }
+
+    /// Build a SOCK_DIAG_BY_FAMILY dump request
+    fn build_request(
+        ip_protocol: u8,
+        family: AddressFamily,
+        states: u32,
+        range: Option<&PortRange>,
+    ) -> Vec<u8> {
+        let bytecode = range.map(Self::port_range_bytecode);
+        let attr_len = bytecode.as_ref().map_or(0, |bc| 4 + bc.len());
+        let total_len = NLMSG_HDR_LEN + DIAG_REQ_LEN + attr_len;
+
+        let mut buf = Vec::with_capacity(total_len);
+
+        // struct nlmsghdr
+        buf.extend_from_slice(&(total_len as u32).to_ne_bytes());
+        buf.extend_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+        buf.extend_from_slice(&((libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16).to_ne_bytes());
+        buf.extend_from_slice(&1u32.to_ne_bytes()); // sequence number
+        buf.extend_from_slice(&0u32.to_ne_bytes()); // port ID (kernel assigns)
+
+        // struct inet_diag_req_v2
+        buf.push(match family {
+            AddressFamily::Ipv4 => libc::AF_INET as u8,
+            AddressFamily::Ipv6 => libc::AF_INET6 as u8,
+        });
+        buf.push(ip_protocol);
+        buf.push(0); // idiag_ext: no extensions requested
+        buf.push(0); // pad
+        buf.extend_from_slice(&states.to_ne_bytes());
+        buf.extend_from_slice(&[0u8; DIAG_REQ_LEN - 8]); // wildcard inet_diag_sockid
+
+        // INET_DIAG_REQ_BYTECODE attribute (struct rtattr + program)
+        if let Some(bytecode) = bytecode {
+            buf.extend_from_slice(&(attr_len as u16).to_ne_bytes());
+            buf.extend_from_slice(&INET_DIAG_REQ_BYTECODE.to_ne_bytes());
+            buf.extend_from_slice(&bytecode);
+        }
+
+        buf
+    }
+
+    /// Build a filter program accepting sockets with `start <= sport <= end`
+    ///
+    /// Each comparison is an instruction followed by an operand instruction whose
+    /// `no` field carries the port. On success execution falls through to the next
+    /// instruction; on failure it jumps past the end of the program, which the
+    /// kernel treats as a rejection.
+    fn port_range_bytecode(range: &PortRange) -> Vec<u8> {
+        let total = (4 * BC_OP_LEN) as u16;
+        let mut bc = Vec::with_capacity(total as usize);
+
+        let comparisons = [
+            (INET_DIAG_BC_S_GE, range.start),
+            (INET_DIAG_BC_S_LE, range.end),
+        ];
+
+        for (i, (code, port)) in comparisons.into_iter().enumerate() {
+            let remaining = total - (i * 2 * BC_OP_LEN) as u16;
+            // struct inet_diag_bc_op { code, yes, no }
+            bc.push(code);
+            bc.push((2 * BC_OP_LEN) as u8);
+            bc.extend_from_slice(&(remaining + 4).to_ne_bytes());
+            // Operand
+            bc.push(0);
+            bc.push(0);
+            bc.extend_from_slice(&port.to_ne_bytes());
+        }
+
+        bc
+    }
+
+    /// Parse a buffer of netlink messages into entries
+    ///
+    /// Returns true once the end of the dump (NLMSG_DONE) has been reached.
+    fn parse_messages(
+        buf: &[u8],
+        protocol: Protocol,
+        entries: &mut Vec<RawSocketEntry>,
+    ) -> Result<bool, ScanError> {
+        let mut offset = 0;
+
+        while offset + NLMSG_HDR_LEN <= buf.len() {
+            let msg_len = read_u32(buf, offset) as usize;
+            let msg_type = read_u16(buf, offset + 4);
+
+            if msg_len < NLMSG_HDR_LEN || offset + msg_len > buf.len() {
+                return Err(ScanError::ParseError(format!(
+                    "Invalid netlink message length: {}",
+                    msg_len
+                )));
+            }
+
+            let payload = &buf[offset + NLMSG_HDR_LEN..offset + msg_len];
+
+            match msg_type as i32 {
+                libc::NLMSG_DONE => return Ok(true),
+                libc::NLMSG_ERROR => {
+                    let errno = if payload.len() >= 4 {
+                        -(read_u32(payload, 0) as i32)
+                    } else {
+                        0
+                    };
+                    return Err(ScanError::NetlinkError(format!(
+                        "Kernel rejected request: {}",
+                        nix::errno::Errno::from_raw(errno)
+                    )));
+                }
+                _ => {
+                    if let Some(entry) = Self::parse_diag_msg(payload, protocol)? {
+                        entries.push(entry);
+                    }
+                }
+            }
+
+            // Messages are aligned to 4 bytes
+            offset += (msg_len + 3) & !3;
+        }
+
+        Ok(false)
+    }
+
+    /// Parse a struct inet_diag_msg into an entry
+    ///
+    /// Layout: family, state, timer, retrans (u8 each), inet_diag_sockid
+    /// (sport, dport as be16; src, dst as 16 bytes; interface; cookie),
+    /// then expires, rqueue, wqueue, uid, inode (u32 each).
+    fn parse_diag_msg(msg: &[u8], protocol: Protocol) -> Result<Option<RawSocketEntry>, ScanError> {
+        if msg.len() < DIAG_MSG_LEN {
+            return Err(ScanError::ParseError(format!(
+                "Truncated inet_diag_msg: {} bytes",
+                msg.len()
+            )));
+        }
+
+        let family = match msg[0] as i32 {
+            libc::AF_INET => AddressFamily::Ipv4,
+            libc::AF_INET6 => AddressFamily::Ipv6,
+            _ => return Ok(None),
+        };
+
+        let state = match protocol {
+            Protocol::Tcp => match TcpState::from_code(msg[1]) {
+                Some(state) => Some(state),
+                None => return Ok(None), // Unknown state code
+            },
+            _ => None,
+        };
+
+        let local_port = u16::from_be_bytes([msg[4], msg[5]]);
+        let remote_port = u16::from_be_bytes([msg[6], msg[7]]);
+        let local_address = format_address(family, &msg[8..24]);
+        let remote_address = format_address(family, &msg[24..40]);
+        let rx_queue = read_u32(msg, 56);
+        // For listeners the kernel reports the backlog limit here, while /proc/net
+        // reports an empty send queue; match /proc so both backends agree
+        let tx_queue = match state {
+            Some(TcpState::Listen) => 0,
+            _ => read_u32(msg, 60),
+        };
+        let uid = read_u32(msg, 64);
+        let inode = read_u32(msg, 68) as u64;
+
+        // Match the /proc parser: skip unbound sockets and those without an inode
+        if local_port == 0 || inode == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(RawSocketEntry {
+            local_address,
+            local_port,
+            remote_address,
+            remote_port,
+            inode,
+            protocol,
+            family,
+            state,
+            tx_queue,
+            rx_queue,
+            uid,
+            netns: None,
+        }))
+    }
+}
+
+/// Format an address from inet_diag_sockid (network byte order)
+fn format_address(family: AddressFamily, bytes: &[u8]) -> String {
+    match family {
+        AddressFamily::Ipv4 => Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).to_string(),
+        AddressFamily::Ipv6 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(&bytes[..16]);
+            Ipv6Addr::from(octets).to_string()
+        }
+    }
+}
+
+/// Read a native-endian u16 at the given offset
+fn read_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_ne_bytes([buf[offset], buf[offset + 1]])
+}
+
+/// Read a native-endian u32 at the given offset
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_ne_bytes([
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::port_scanner::PortScanner;
+
+    #[test]
+    fn test_build_request_without_filter() {
+        let req = SockDiag::build_request(6, AddressFamily::Ipv4, 1 << 10, None);
+        assert_eq!(req.len(), NLMSG_HDR_LEN + DIAG_REQ_LEN);
+        assert_eq!(read_u32(&req, 0) as usize, req.len());
+        assert_eq!(read_u16(&req, 4), SOCK_DIAG_BY_FAMILY);
+        assert_eq!(req[NLMSG_HDR_LEN], libc::AF_INET as u8);
+        assert_eq!(req[NLMSG_HDR_LEN + 1], 6);
+        assert_eq!(read_u32(&req, NLMSG_HDR_LEN + 4), 1 << 10);
+    }
+
+    #[test]
+    fn test_build_request_with_port_range() {
+        let range = PortRange {
+            start: 3000,
+            end: 3010,
+        };
+        let req = SockDiag::build_request(17, AddressFamily::Ipv6, ALL_STATES, Some(&range));
+        assert_eq!(read_u32(&req, 0) as usize, req.len());
+
+        let attr = &req[NLMSG_HDR_LEN + DIAG_REQ_LEN..];
+        assert_eq!(read_u16(attr, 0) as usize, attr.len());
+        assert_eq!(read_u16(attr, 2), INET_DIAG_REQ_BYTECODE);
+
+        let bc = &attr[4..];
+        assert_eq!(bc[0], INET_DIAG_BC_S_GE);
+        assert_eq!(read_u16(bc, 6), 3000);
+        assert_eq!(bc[8], INET_DIAG_BC_S_LE);
+        assert_eq!(read_u16(bc, 14), 3010);
+    }
+
+    #[test]
+    fn test_parse_diag_msg_listen() {
+        let mut msg = vec![0u8; DIAG_MSG_LEN];
+        msg[0] = libc::AF_INET as u8;
+        msg[1] = 0x0A; // LISTEN
+        msg[4..6].copy_from_slice(&8080u16.to_be_bytes());
+        msg[8..12].copy_from_slice(&[127, 0, 0, 1]);
+        msg[56..60].copy_from_slice(&3u32.to_ne_bytes()); // accept queue
+        msg[60..64].copy_from_slice(&128u32.to_ne_bytes()); // backlog limit
+        msg[64..68].copy_from_slice(&1000u32.to_ne_bytes());
+        msg[68..72].copy_from_slice(&12345u32.to_ne_bytes());
+
+        let entry = SockDiag::parse_diag_msg(&msg, Protocol::Tcp)
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.local_address, "127.0.0.1");
+        assert_eq!(entry.local_port, 8080);
+        assert_eq!(entry.remote_address, "0.0.0.0");
+        assert_eq!(entry.remote_port, 0);
+        assert_eq!(entry.inode, 12345);
+        assert_eq!(entry.state, Some(TcpState::Listen));
+        assert_eq!(entry.rx_queue, 3);
+        assert_eq!(entry.tx_queue, 0);
+        assert_eq!(entry.uid, 1000);
+    }
+
+    /// A sock_diag dump as the kernel sends it: one established TCP socket over
+    /// IPv6, then NLMSG_DONE (little-endian host)
+    #[cfg(target_endian = "little")]
+    #[rustfmt::skip]
+    const ESTABLISHED_DUMP: [u8; 108] = [
+        // nlmsghdr: length 88, SOCK_DIAG_BY_FAMILY, NLM_F_MULTI, seq 1, port 0
+        0x58, 0x00, 0x00, 0x00, 0x14, 0x00, 0x02, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+        // inet_diag_msg: AF_INET6, ESTABLISHED, timer, retrans
+        0x0A, 0x01, 0x00, 0x00,
+        // sport 5432, dport 51000 (big-endian)
+        0x15, 0x38, 0xC7, 0x38,
+        // src ::1
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x01,
+        // dst 2001:db8::2
+        0x20, 0x01, 0x0D, 0xB8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x02,
+        // interface, cookie
+        0x00, 0x00, 0x00, 0x00, 0xEF, 0xBE, 0xAD, 0xDE, 0x01, 0x00, 0x00, 0x00,
+        // expires, rqueue 7 (offset 56), wqueue 42 (60), uid 1000 (64), inode 424242 (68)
+        0x00, 0x00, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00, 0x2A, 0x00, 0x00, 0x00, 0xE8, 0x03,
+        0x00, 0x00, 0x32, 0x79, 0x06, 0x00,
+        // nlmsghdr: length 20, NLMSG_DONE, NLM_F_MULTI, seq 1, port 0; status 0
+        0x14, 0x00, 0x00, 0x00, 0x03, 0x00, 0x02, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn test_parse_messages_fixture() {
+        let mut entries = Vec::new();
+        let done =
+            SockDiag::parse_messages(&ESTABLISHED_DUMP, Protocol::Tcp, &mut entries).unwrap();
+        assert!(done);
+
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.family, AddressFamily::Ipv6);
+        assert_eq!(entry.state, Some(TcpState::Established));
+        assert_eq!(entry.local_address, "::1");
+        assert_eq!(entry.local_port, 5432);
+        assert_eq!(entry.remote_address, "2001:db8::2");
+        assert_eq!(entry.remote_port, 51000);
+        assert_eq!(entry.rx_queue, 7);
+        assert_eq!(entry.tx_queue, 42);
+        assert_eq!(entry.uid, 1000);
+        assert_eq!(entry.inode, 424242);
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn test_parse_messages_matches_proc_line() {
+        // The same socket as ESTABLISHED_DUMP, as /proc/net/tcp6 shows it
+        let line = "   0: 00000000000000000000000001000000:1538 \
+                    B80D0120000000000000000002000000:C738 01 0000002A:00000007 00:00000000 \
+                    00000000  1000        0 424242 1 0000000000000000 20 4 30 10 -1";
+        let proc = PortScanner::parse_line(line, Protocol::Tcp, AddressFamily::Ipv6, ScanMode::All)
+            .unwrap()
+            .unwrap();
+
+        let mut entries = Vec::new();
+        SockDiag::parse_messages(&ESTABLISHED_DUMP, Protocol::Tcp, &mut entries).unwrap();
+
+        assert_eq!(entries, vec![proc]);
+    }
+
+    #[test]
+    fn test_supports() {
+        assert!(SockDiag::supports(Protocol::Tcp));
+        assert!(SockDiag::supports(Protocol::UdpLite));
+        assert!(!SockDiag::supports(Protocol::Raw));
+        assert!(!SockDiag::supports(Protocol::Icmp));
+    }
+
+    #[test]
+    fn test_parse_diag_msg_truncated() {
+        assert!(SockDiag::parse_diag_msg(&[0u8; 16], Protocol::Tcp).is_err());
+    }
+}
@@ -1,20 +1,90 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Information about a process listening on a network port
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortInfo {
-    /// Process ID
-    pub pid: u32,
-    /// Name of the process (e.g., "node", "python3")
-    pub process_name: String,
+    /// Process ID (None if the owning process can't be inspected, e.g. another user's)
+    ///
+    /// For sockets shared by several processes, this is the master's.
+    pub pid: Option<u32>,
+    /// Name of the process (e.g., "node", "python3"), None when the process is hidden
+    pub process_name: Option<String>,
+    /// Identity of the process at scan time, to check before killing it
+    pub identity: Option<ProcessIdentity>,
     /// Port number
     pub port: u16,
-    /// Protocol (TCP or UDP)
+    /// Protocol (TCP, UDP, UDP-Lite, raw, ICMP or SCTP)
     pub protocol: Protocol,
-    /// Local address (e.g., "0.0.0.0", "127.0.0.1")
+    /// Local address (e.g., "0.0.0.0", "127.0.0.1", "::")
     pub address: String,
+    /// Address family of the socket (IPv4 or IPv6)
+    pub family: AddressFamily,
+    /// TCP connection state (None for connectionless protocols)
+    pub state: Option<TcpState>,
+    /// Peer address for connected sockets (None when not connected)
+    pub remote_address: Option<String>,
+    /// Peer port for connected sockets (None when not connected)
+    pub remote_port: Option<u16>,
+    /// Send queue in bytes (always 0 for LISTEN sockets)
+    pub tx_queue: u32,
+    /// Receive queue in bytes; for LISTEN sockets, connections waiting to be accepted
+    pub rx_queue: u32,
+    /// Listener whose accept queue stayed non-empty across consecutive scans
+    pub backlog_stalled: bool,
+    /// Inode of the network namespace the socket lives in (e.g., 4026531840)
+    pub netns: Option<u64>,
+    /// Container the owning process runs in (None for host processes)
+    pub container: Option<ContainerInfo>,
+    /// Every process holding the socket, master first (empty when the process is hidden)
+    pub owners: Vec<SocketOwner>,
+    /// UID of the socket's owner
+    pub uid: u32,
+    /// Username of the process owner, or of the socket owner when the process is hidden
+    pub user: String,
+}
+
+/// A process holding a socket, e.g. an nginx master or one of its workers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SocketOwner {
+    /// Process ID
+    pub pid: u32,
+    /// Parent process ID (0 if unknown)
+    pub ppid: u32,
+    /// Name of the process
+    pub process_name: String,
     /// Username of process owner
     pub user: String,
+    /// Start time in clock ticks after boot, to check before killing it
+    pub start_time: Option<u64>,
+    /// Whether this is the master process the others were forked from
+    pub master: bool,
+}
+
+/// Information about a Unix domain socket and the process that owns it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnixSocketInfo {
+    /// Process ID (None if the owning process can't be inspected, e.g. another user's)
+    pub pid: Option<u32>,
+    /// Name of the process (e.g., "dockerd", "postgres"), None when the process is hidden
+    pub process_name: Option<String>,
+    /// Filesystem path the socket is bound to (e.g., "/run/docker.sock")
+    pub path: Option<String>,
+    /// Abstract namespace name, without the leading '@'
+    pub abstract_name: Option<String>,
+    /// Socket type (stream, datagram or seqpacket)
+    pub socket_type: UnixSocketType,
+    /// Connection state of the socket
+    pub state: UnixSocketState,
+    /// Whether the socket is accepting connections
+    pub listening: bool,
+    /// Socket inode number
+    pub inode: u64,
+    /// Username of the process owner, or of the socket file when the process is hidden
+    ///
+    /// None for abstract and unnamed sockets of hidden processes, which have no owner
+    /// to read without the process.
+    pub user: Option<String>,
 }
 
 /// Network protocol type
@@ -22,6 +92,14 @@ pub struct PortInfo {
 pub enum Protocol {
     Tcp,
     Udp,
+    /// UDP-Lite (RFC 3828)
+    UdpLite,
+    /// Raw IP socket; the "port" is the IP protocol number it receives
+    Raw,
+    /// ICMP echo ("ping") socket; the "port" is the ICMP identifier
+    Icmp,
+    /// SCTP endpoint or association
+    Sctp,
 }
 
 impl std::fmt::Display for Protocol {
@@ -29,8 +107,269 @@ impl std::fmt::Display for Protocol {
         match self {
             Protocol::Tcp => write!(f, "TCP"),
             Protocol::Udp => write!(f, "UDP"),
+            Protocol::UdpLite => write!(f, "UDP-Lite"),
+            Protocol::Raw => write!(f, "RAW"),
+            Protocol::Icmp => write!(f, "ICMP"),
+            Protocol::Sctp => write!(f, "SCTP"),
+        }
+    }
+}
+
+impl Protocol {
+    /// Whether sockets of this protocol bind real ports
+    ///
+    /// Raw and ICMP sockets report the IP protocol number and echo ID instead.
+    pub fn has_ports(self) -> bool {
+        !matches!(self, Protocol::Raw | Protocol::Icmp)
+    }
+}
+
+/// IP address family of a socket
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum AddressFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl std::fmt::Display for AddressFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressFamily::Ipv4 => write!(f, "IPv4"),
+            AddressFamily::Ipv6 => write!(f, "IPv6"),
+        }
+    }
+}
+
+/// Container runtime a process was started by
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+    Containerd,
+    CriO,
+}
+
+impl std::fmt::Display for ContainerRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContainerRuntime::Docker => write!(f, "Docker"),
+            ContainerRuntime::Podman => write!(f, "Podman"),
+            ContainerRuntime::Containerd => write!(f, "containerd"),
+            ContainerRuntime::CriO => write!(f, "CRI-O"),
+        }
+    }
+}
+
+/// Container a process belongs to, detected from its cgroup
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContainerInfo {
+    /// Runtime managing the container
+    pub runtime: ContainerRuntime,
+    /// Full 64-character container ID
+    pub id: String,
+    /// Container name from the runtime API (None if the runtime socket is unreachable)
+    pub name: Option<String>,
+}
+
+/// TCP connection state, as reported in the `st` column of /proc/net/tcp
+///
+/// Values match the kernel's `TCP_*` constants from include/net/tcp_states.h.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum TcpState {
+    Established,
+    SynSent,
+    SynRecv,
+    FinWait1,
+    FinWait2,
+    TimeWait,
+    Close,
+    CloseWait,
+    LastAck,
+    Listen,
+    Closing,
+    NewSynRecv,
+}
+
+impl TcpState {
+    /// Convert a kernel state code (e.g., 0x0A) into a TcpState
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0x01 => Some(TcpState::Established),
+            0x02 => Some(TcpState::SynSent),
+            0x03 => Some(TcpState::SynRecv),
+            0x04 => Some(TcpState::FinWait1),
+            0x05 => Some(TcpState::FinWait2),
+            0x06 => Some(TcpState::TimeWait),
+            0x07 => Some(TcpState::Close),
+            0x08 => Some(TcpState::CloseWait),
+            0x09 => Some(TcpState::LastAck),
+            0x0A => Some(TcpState::Listen),
+            0x0B => Some(TcpState::Closing),
+            0x0C => Some(TcpState::NewSynRecv),
+            _ => None,
+        }
+    }
+
+    /// The kernel state code for this state
+    pub fn code(self) -> u8 {
+        match self {
+            TcpState::Established => 0x01,
+            TcpState::SynSent => 0x02,
+            TcpState::SynRecv => 0x03,
+            TcpState::FinWait1 => 0x04,
+            TcpState::FinWait2 => 0x05,
+            TcpState::TimeWait => 0x06,
+            TcpState::Close => 0x07,
+            TcpState::CloseWait => 0x08,
+            TcpState::LastAck => 0x09,
+            TcpState::Listen => 0x0A,
+            TcpState::Closing => 0x0B,
+            TcpState::NewSynRecv => 0x0C,
+        }
+    }
+}
+
+impl std::fmt::Display for TcpState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TcpState::Established => "ESTABLISHED",
+            TcpState::SynSent => "SYN_SENT",
+            TcpState::SynRecv => "SYN_RECV",
+            TcpState::FinWait1 => "FIN_WAIT1",
+            TcpState::FinWait2 => "FIN_WAIT2",
+            TcpState::TimeWait => "TIME_WAIT",
+            TcpState::Close => "CLOSE",
+            TcpState::CloseWait => "CLOSE_WAIT",
+            TcpState::LastAck => "LAST_ACK",
+            TcpState::Listen => "LISTEN",
+            TcpState::Closing => "CLOSING",
+            TcpState::NewSynRecv => "NEW_SYN_RECV",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Which sockets a scan should return
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ScanMode {
+    /// Only listening TCP sockets and bound UDP sockets
+    #[default]
+    Listening,
+    /// Every socket regardless of state, including live connections
+    All,
+}
+
+/// Mechanism used to enumerate sockets
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ScanBackend {
+    /// Netlink sock_diag, falling back to /proc per table when unavailable
+    #[default]
+    Auto,
+    /// Netlink sock_diag only (tables it can't serve are still read from /proc)
+    Netlink,
+    /// Text parsing of /proc/net/* only
+    Proc,
+}
+
+/// The signal that ended a killed process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KillStage {
+    /// Exited on SIGTERM within the grace period
+    Sigterm,
+    /// Ignored SIGTERM and had to be sent SIGKILL
+    Sigkill,
+}
+
+/// Why a process is refused to be signalled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProtectionReason {
+    /// PID 1: init, usually systemd
+    Init,
+    /// PortWatcher's own process
+    PortWatcher,
+    /// Leader of PortWatcher's session; killing it ends the login session
+    SessionLeader,
+    /// X server or Wayland compositor
+    DisplayServer,
+    /// Built-in system service such as sshd or the D-Bus daemon
+    SystemService,
+    /// Listed under `protected_processes` in the user's config
+    Configured,
+}
+
+/// A specific process: its PID plus the time it started, which a reused PID won't share
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessIdentity {
+    /// Process ID
+    pub pid: u32,
+    /// Start time in clock ticks after boot (field 22 of /proc/[pid]/stat)
+    pub start_time: u64,
+}
+
+/// One process of a batch kill, optionally pinned to the start time it was listed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KillTarget {
+    /// Process ID
+    pub pid: u32,
+    /// Start time from `PortInfo.identity`; the kill is refused if the PID's differs
+    #[serde(default)]
+    pub start_time: Option<u64>,
+    /// Port the process was listed under, kept in the kill history
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+/// Signals that may be sent to a process
+///
+/// Deliberately a closed set: the frontend can't ask for arbitrary signal numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProcessSignal {
+    /// Ask the process to exit
+    Sigterm,
+    /// Interrupt, as Ctrl-C in a terminal would
+    Sigint,
+    /// Hang up; most daemons reload their configuration
+    Sighup,
+    /// Kill immediately, can't be caught
+    Sigkill,
+    /// Pause the process, keeping its state and sockets
+    Sigstop,
+    /// Resume a paused process
+    Sigcont,
+}
+
+impl ProcessSignal {
+    /// Name as accepted by kill(1), e.g. "TERM" for `kill -TERM`
+    pub fn kill_name(&self) -> &'static str {
+        match self {
+            ProcessSignal::Sigterm => "TERM",
+            ProcessSignal::Sigint => "INT",
+            ProcessSignal::Sighup => "HUP",
+            ProcessSignal::Sigkill => "KILL",
+            ProcessSignal::Sigstop => "STOP",
+            ProcessSignal::Sigcont => "CONT",
         }
     }
+
+    /// Signal from its kill(1) name, None for signals outside the allowlist
+    pub fn from_kill_name(name: &str) -> Option<Self> {
+        [
+            ProcessSignal::Sigterm,
+            ProcessSignal::Sigint,
+            ProcessSignal::Sighup,
+            ProcessSignal::Sigkill,
+            ProcessSignal::Sigstop,
+            ProcessSignal::Sigcont,
+        ]
+        .into_iter()
+        .find(|signal| signal.kill_name() == name)
+    }
+}
+
+impl std::fmt::Display for ProcessSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SIG{}", self.kill_name())
+    }
 }
 
 /// Result of a kill process operation
@@ -38,28 +377,174 @@ impl std::fmt::Display for Protocol {
 #[serde(tag = "status")]
 pub enum KillResult {
     /// Process was successfully terminated
-    Success,
+    Success { stage: KillStage },
+    /// Signal was delivered (the process may well still be running)
+    Signalled { signal: ProcessSignal },
     /// Permission denied - process owned by another user
     PermissionDenied { pid: u32 },
     /// Elevation required - need root privileges
     ElevationRequired { pid: u32 },
     /// Process not found (may have already exited)
     ProcessNotFound { pid: u32 },
+    /// The PID now belongs to a different process than the one listed
+    IdentityMismatch { pid: u32 },
+    /// Protected process; nothing was sent to it
+    Protected { pid: u32, reason: ProtectionReason },
+    /// The password dialog of an elevated request was dismissed; nothing was sent
+    AuthenticationCancelled,
+    /// polkit refused to run the privileged helper; nothing was sent
+    NotAuthorized,
     /// Other error occurred
     Error { message: String },
 }
 
-/// Raw socket entry parsed from /proc/net/tcp or /proc/net/udp
-#[derive(Debug, Clone)]
+/// Outcome of signalling one process of a batch, tree or group kill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessKillResult {
+    /// Process ID that was signalled
+    pub pid: u32,
+    /// What happened to it
+    pub result: KillResult,
+}
+
+/// One attempt to kill or signal a process, as kept in the kill history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillRecord {
+    /// Identifies the record, e.g. to relaunch the process (0 in old logs)
+    #[serde(default)]
+    pub id: u64,
+    /// When the attempt finished, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Process ID
+    pub pid: u32,
+    /// Process name before it was signalled (None if it couldn't be read)
+    pub name: Option<String>,
+    /// Command line before it was signalled (empty if unreadable)
+    pub cmdline: Vec<String>,
+    /// Working directory before it was signalled (None if unreadable)
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Environment before it was signalled, kept for single-process kills only
+    ///
+    /// None for signals and batch kills, which can't be relaunched, or if
+    /// unreadable (e.g. another user's process).
+    #[serde(default)]
+    pub environ: Option<BTreeMap<String, String>>,
+    /// Port the process was killed for, if known
+    pub port: Option<u16>,
+    /// Signal requested; SIGTERM for kills, which may have escalated (see outcome)
+    pub signal: ProcessSignal,
+    /// Whether it went through pkexec
+    pub elevated: bool,
+    /// What happened
+    pub outcome: KillResult,
+}
+
+/// A kill history record as shown in the UI, without the recorded environment
+///
+/// The environment stays in the backend, for `relaunch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillHistoryEntry {
+    /// Identifies the record, e.g. to relaunch the process
+    pub id: u64,
+    /// When the attempt finished, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Process ID
+    pub pid: u32,
+    /// Process name before it was signalled (None if it couldn't be read)
+    pub name: Option<String>,
+    /// Command line before it was signalled (empty if unreadable)
+    pub cmdline: Vec<String>,
+    /// Working directory before it was signalled (None if unreadable)
+    pub cwd: Option<String>,
+    /// Port the process was killed for, if known
+    pub port: Option<u16>,
+    /// Signal requested; SIGTERM for kills, which may have escalated (see outcome)
+    pub signal: ProcessSignal,
+    /// Whether it went through pkexec
+    pub elevated: bool,
+    /// What happened
+    pub outcome: KillResult,
+}
+
+impl From<KillRecord> for KillHistoryEntry {
+    fn from(record: KillRecord) -> Self {
+        Self {
+            id: record.id,
+            timestamp: record.timestamp,
+            pid: record.pid,
+            name: record.name,
+            cmdline: record.cmdline,
+            cwd: record.cwd,
+            port: record.port,
+            signal: record.signal,
+            elevated: record.elevated,
+            outcome: record.outcome,
+        }
+    }
+}
+
+/// Raw socket entry parsed from /proc/net/{tcp,udp,udplite,raw,icmp}{,6} or /proc/net/sctp/
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RawSocketEntry {
-    /// Local IP address in dotted notation
+    /// Local IP address (dotted notation for IPv4, RFC 5952 form for IPv6)
     pub local_address: String,
     /// Local port number
     pub local_port: u16,
+    /// Remote (peer) IP address, the wildcard address when not connected
+    pub remote_address: String,
+    /// Remote (peer) port number, 0 when not connected
+    pub remote_port: u16,
     /// Socket inode number (used to map to process)
     pub inode: u64,
     /// Protocol type
     pub protocol: Protocol,
+    /// Address family of the socket table the entry was read from
+    pub family: AddressFamily,
+    /// TCP connection state (None for connectionless protocols)
+    pub state: Option<TcpState>,
+    /// Send queue in bytes (always 0 for LISTEN sockets)
+    pub tx_queue: u32,
+    /// Receive queue in bytes; for LISTEN sockets, connections waiting to be accepted
+    pub rx_queue: u32,
+    /// UID of the socket's owner, as reported by the kernel
+    pub uid: u32,
+    /// Inode of the network namespace the socket lives in (None if unknown)
+    pub netns: Option<u64>,
+}
+
+/// Raw Unix domain socket entry parsed from /proc/net/unix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnixSocketEntry {
+    /// Filesystem path the socket is bound to
+    pub path: Option<String>,
+    /// Abstract namespace name, without the leading '@'
+    pub abstract_name: Option<String>,
+    /// Socket type
+    pub socket_type: UnixSocketType,
+    /// Connection state
+    pub state: UnixSocketState,
+    /// Whether the socket is accepting connections (__SO_ACCEPTCON flag)
+    pub listening: bool,
+    /// Socket inode number (used to map to process)
+    pub inode: u64,
+}
+
+/// Unix domain socket type, from the `Type` column of /proc/net/unix
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum UnixSocketType {
+    Stream,
+    Dgram,
+    SeqPacket,
+}
+
+/// Unix domain socket state, from the `St` column of /proc/net/unix
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum UnixSocketState {
+    Unconnected,
+    Connecting,
+    Connected,
+    Disconnecting,
 }
 
 /// Error type for port scanning operations
@@ -73,17 +558,100 @@ pub enum ScanError {
     },
     #[error("Failed to parse line: {0}")]
     ParseError(String),
+    #[error("Netlink sock_diag error: {0}")]
+    NetlinkError(String),
+}
+
+/// Error type for relaunching a killed process
+#[derive(Debug, thiserror::Error)]
+pub enum RelaunchError {
+    #[error("No kill with id {0} in the history")]
+    NotFound(u64),
+    #[error("Process {0} wasn't stopped by that attempt and may still be running")]
+    NotStopped(u32),
+    #[error("The {0} of process {1} wasn't recorded, so it can't be relaunched")]
+    NotRecorded(&'static str, u32),
+    #[error("Failed to start {program}: {source}")]
+    SpawnError {
+        program: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Error type for requests to the privileged helper
+#[derive(Debug, thiserror::Error)]
+pub enum HelperRequestError {
+    #[error("usage: kill <grace_ms> <escalate 0|1> <pid:start_time>... | signal <NAME> <pid:start_time>")]
+    Usage,
+    #[error("Invalid process {0:?}, expected <pid>:<start_time>")]
+    InvalidTarget(String),
+    #[error("Signal {0:?} is not allowed")]
+    InvalidSignal(String),
+    #[error("Invalid {0}: {1:?}")]
+    InvalidValue(&'static str, String),
+}
+
+/// A killed process started again from the kill history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelaunchResult {
+    /// Kill history record it was started from
+    pub kill_id: u64,
+    /// PID of the new process
+    pub pid: u32,
+    /// Port the process was killed for, if known
+    pub port: Option<u16>,
+    /// Whether the new process (or one of its children) listens on that port again;
+    /// None without a port
+    pub port_reacquired: Option<bool>,
 }
 
 /// Process information resolved from inode
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
     /// Process ID
     pub pid: u32,
+    /// Parent process ID (0 if unknown)
+    pub ppid: u32,
     /// Process name from /proc/[pid]/comm
     pub name: String,
     /// Username of process owner
     pub user: String,
+    /// Start time in clock ticks after boot (None if unreadable)
+    pub start_time: Option<u64>,
+    /// Container the process runs in, if any
+    pub container: Option<ContainerInfo>,
+}
+
+/// Detailed information about a single process, read from /proc/[pid]/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessDetails {
+    /// Process ID
+    pub pid: u32,
+    /// Parent process ID (0 if unknown)
+    pub ppid: u32,
+    /// Process name from /proc/[pid]/comm (at most 15 characters)
+    pub name: String,
+    /// Full command line, one element per argument (empty for kernel threads)
+    pub cmdline: Vec<String>,
+    /// Path of the executable (None if unreadable, e.g. another user's process)
+    pub exe: Option<String>,
+    /// Current working directory (None if unreadable)
+    pub cwd: Option<String>,
+    /// Start time as seconds since the Unix epoch
+    pub start_time: Option<u64>,
+    /// Number of threads
+    pub threads: u32,
+    /// Resident set size in bytes
+    pub rss_bytes: u64,
+    /// User plus system CPU time consumed, in milliseconds
+    pub cpu_time_ms: u64,
+    /// Number of open file descriptors (None if unreadable)
+    pub fd_count: Option<usize>,
+    /// Controlling terminal (e.g. "/dev/pts/3"), None for daemons
+    pub tty: Option<String>,
+    /// Username of process owner
+    pub user: String,
 }
 
 /// A range of ports to scan
@@ -110,15 +678,34 @@ pub struct PortPreset {
     pub ports: Vec<u16>,
 }
 
+/// Outcome of freeing a port: the processes stopped and what still holds it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FreePortResult {
+    /// Port that was freed
+    pub port: u16,
+    /// Protocol it was freed for (None for every protocol with ports)
+    pub protocol: Option<Protocol>,
+    /// One result per process that held the port, masters first
+    pub stopped: Vec<ProcessKillResult>,
+    /// Sockets still bound to the port after the kill, from a fresh scan
+    pub remaining: Vec<PortInfo>,
+    /// Whether nothing is bound to the port anymore
+    pub port_free: bool,
+}
+
 /// Statistics for the system tray tooltip
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrayStats {
-    /// Total number of listening ports
+    /// Total number of listening ports, not counting raw and ICMP sockets
     pub total_ports: usize,
     /// Number of TCP ports
     pub tcp_count: usize,
     /// Number of UDP ports
     pub udp_count: usize,
+    /// Number of UDP-Lite and SCTP ports
+    pub other_count: usize,
+    /// Number of raw and ICMP sockets, which have no port
+    pub raw_count: usize,
     /// Number of unique processes
     pub process_count: usize,
 }
@@ -131,21 +718,112 @@ mod tests {
     fn test_protocol_display() {
         assert_eq!(Protocol::Tcp.to_string(), "TCP");
         assert_eq!(Protocol::Udp.to_string(), "UDP");
+        assert_eq!(Protocol::UdpLite.to_string(), "UDP-Lite");
+        assert_eq!(Protocol::Raw.to_string(), "RAW");
+        assert_eq!(Protocol::Icmp.to_string(), "ICMP");
+        assert_eq!(Protocol::Sctp.to_string(), "SCTP");
+    }
+
+    #[test]
+    fn test_protocol_has_ports() {
+        assert!(Protocol::Tcp.has_ports());
+        assert!(Protocol::UdpLite.has_ports());
+        assert!(Protocol::Sctp.has_ports());
+        assert!(!Protocol::Raw.has_ports());
+        assert!(!Protocol::Icmp.has_ports());
+    }
+
+    #[test]
+    fn test_address_family_display() {
+        assert_eq!(AddressFamily::Ipv4.to_string(), "IPv4");
+        assert_eq!(AddressFamily::Ipv6.to_string(), "IPv6");
+    }
+
+    #[test]
+    fn test_container_runtime_display() {
+        assert_eq!(ContainerRuntime::Docker.to_string(), "Docker");
+        assert_eq!(ContainerRuntime::Containerd.to_string(), "containerd");
+        assert_eq!(ContainerRuntime::CriO.to_string(), "CRI-O");
+    }
+
+    #[test]
+    fn test_process_signal_names() {
+        assert_eq!(ProcessSignal::Sighup.kill_name(), "HUP");
+        assert_eq!(ProcessSignal::Sigstop.to_string(), "SIGSTOP");
+
+        let signal: ProcessSignal = serde_json::from_str("\"Sigcont\"").unwrap();
+        assert_eq!(signal, ProcessSignal::Sigcont);
+        assert!(serde_json::from_str::<ProcessSignal>("\"Sigsegv\"").is_err());
+
+        assert_eq!(
+            ProcessSignal::from_kill_name("STOP"),
+            Some(ProcessSignal::Sigstop)
+        );
+        assert_eq!(ProcessSignal::from_kill_name("SEGV"), None);
+    }
+
+    #[test]
+    fn test_tcp_state_from_code() {
+        assert_eq!(TcpState::from_code(0x01), Some(TcpState::Established));
+        assert_eq!(TcpState::from_code(0x06), Some(TcpState::TimeWait));
+        assert_eq!(TcpState::from_code(0x08), Some(TcpState::CloseWait));
+        assert_eq!(TcpState::from_code(0x0A), Some(TcpState::Listen));
+        assert_eq!(TcpState::from_code(0x00), None);
+        assert_eq!(TcpState::from_code(0x0D), None);
+    }
+
+    #[test]
+    fn test_tcp_state_code_roundtrip() {
+        for code in 0x01..=0x0C {
+            assert_eq!(TcpState::from_code(code).unwrap().code(), code);
+        }
+    }
+
+    #[test]
+    fn test_tcp_state_display() {
+        assert_eq!(TcpState::Listen.to_string(), "LISTEN");
+        assert_eq!(TcpState::FinWait2.to_string(), "FIN_WAIT2");
     }
 
     #[test]
     fn test_port_info_serialization() {
         let info = PortInfo {
-            pid: 1234,
-            process_name: "test".to_string(),
+            pid: Some(1234),
+            process_name: Some("test".to_string()),
+            identity: Some(ProcessIdentity {
+                pid: 1234,
+                start_time: 5678,
+            }),
             port: 8080,
             protocol: Protocol::Tcp,
             address: "127.0.0.1".to_string(),
+            family: AddressFamily::Ipv4,
+            state: Some(TcpState::Listen),
+            remote_address: None,
+            remote_port: None,
+            tx_queue: 0,
+            rx_queue: 0,
+            backlog_stalled: false,
+            netns: Some(4026531840),
+            container: None,
+            owners: vec![SocketOwner {
+                pid: 1234,
+                ppid: 1,
+                process_name: "test".to_string(),
+                user: "testuser".to_string(),
+                start_time: Some(5678),
+                master: true,
+            }],
+            uid: 1000,
             user: "testuser".to_string(),
         };
         let json = serde_json::to_string(&info).unwrap();
         assert!(json.contains("\"pid\":1234"));
         assert!(json.contains("\"protocol\":\"Tcp\""));
+        assert!(json.contains("\"family\":\"Ipv4\""));
+        assert!(json.contains("\"state\":\"Listen\""));
+        assert!(json.contains("\"master\":true"));
+        assert!(json.contains("\"identity\":{\"pid\":1234,\"start_time\":5678}"));
     }
 
     #[test]
@@ -154,5 +832,11 @@ mod tests {
         let json = serde_json::to_string(&result).unwrap();
         assert!(json.contains("\"status\":\"PermissionDenied\""));
         assert!(json.contains("\"pid\":1234"));
+
+        let result = KillResult::Success {
+            stage: KillStage::Sigkill,
+        };
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(json, r#"{"status":"Success","stage":"Sigkill"}"#);
     }
 }
@@ -0,0 +1,179 @@
+//! Builder for fake procfs trees used by the fixture tests
+
+use portwatcher_lib::procfs::ProcFs;
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+/// Header line of /proc/net/{tcp,udp,...}; the parser skips it
+const INET_HEADER: &str = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode";
+
+/// Header line of /proc/net/unix
+const UNIX_HEADER: &str = "Num       RefCount Protocol Flags    Type St Inode Path";
+
+/// A temporary directory laid out like /proc, removed on drop
+pub struct FakeProc {
+    root: PathBuf,
+}
+
+impl FakeProc {
+    /// Create an empty tree with the always-present IPv4 TCP and UDP tables
+    pub fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!(
+            "portwatcher-fixture-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("net")).unwrap();
+
+        let fake = Self { root };
+        fake.inet_table("net/tcp", &[]);
+        fake.inet_table("net/udp", &[]);
+        fake
+    }
+
+    /// A ProcFs pointing at this tree
+    pub fn procfs(&self) -> ProcFs {
+        ProcFs::new(&self.root)
+    }
+
+    /// Root directory of the tree
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Write a file relative to the root, creating parent directories
+    pub fn file(&self, rel: &str, content: &str) -> &Self {
+        let path = self.root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        self
+    }
+
+    /// Write an inet socket table (e.g. "net/tcp6" or "200/net/tcp") from rows
+    pub fn inet_table(&self, rel: &str, rows: &[String]) -> &Self {
+        let mut content = format!("{}\n", INET_HEADER);
+        for row in rows {
+            content.push_str(row);
+            content.push('\n');
+        }
+        self.file(rel, &content)
+    }
+
+    /// Write /proc/net/unix from rows
+    pub fn unix_table(&self, rows: &[&str]) -> &Self {
+        let mut content = format!("{}\n", UNIX_HEADER);
+        for row in rows {
+            content.push_str(row);
+            content.push('\n');
+        }
+        self.file("net/unix", &content)
+    }
+
+    /// Add a process with a name, owner and open socket inodes
+    pub fn process(&self, pid: u32, comm: &str, uid: u32, sockets: &[u64]) -> &Self {
+        self.child_process(pid, 1, comm, uid, sockets)
+    }
+
+    /// Add a process forked from `ppid`, e.g. a worker inheriting its master's sockets
+    pub fn child_process(
+        &self,
+        pid: u32,
+        ppid: u32,
+        comm: &str,
+        uid: u32,
+        sockets: &[u64],
+    ) -> &Self {
+        self.file(&format!("{}/comm", pid), &format!("{}\n", comm));
+        self.file(
+            &format!("{}/status", pid),
+            &format!(
+                "Name:\t{}\nPid:\t{}\nPPid:\t{}\nUid:\t{}\t{}\t{}\t{}\n",
+                comm, pid, ppid, uid, uid, uid, uid
+            ),
+        );
+
+        self.start_time(pid, ppid, comm, 1000 + pid as u64);
+
+        fs::create_dir_all(self.root.join(format!("{}/fd", pid))).unwrap();
+        for (i, inode) in sockets.iter().enumerate() {
+            self.socket_fd(pid, i as u32 + 3, *inode);
+        }
+        self
+    }
+
+    /// Write /proc/[pid]/stat with the given start time (field 22)
+    ///
+    /// Changing it makes the PID look like a new process that reused the number.
+    pub fn start_time(&self, pid: u32, ppid: u32, comm: &str, ticks: u64) -> &Self {
+        self.file(
+            &format!("{}/stat", pid),
+            &format!(
+                "{} ({}) S {} 0 0 0 -1 0 0 0 0 0 0 0 0 0 20 0 1 0 {} 0 0\n",
+                pid, comm, ppid, ticks
+            ),
+        )
+    }
+
+    /// Point an fd of a process at a socket, replacing whatever it held
+    pub fn socket_fd(&self, pid: u32, fd: u32, inode: u64) -> &Self {
+        let link = self.root.join(format!("{}/fd/{}", pid, fd));
+        let _ = fs::remove_file(&link);
+        symlink(format!("socket:[{}]", inode), link).unwrap();
+        self
+    }
+
+    /// Point an fd of a process at a regular file, replacing whatever it held
+    pub fn file_fd(&self, pid: u32, fd: u32, path: &str) -> &Self {
+        let link = self.root.join(format!("{}/fd/{}", pid, fd));
+        let _ = fs::remove_file(&link);
+        symlink(path, link).unwrap();
+        self
+    }
+
+    /// Close an fd of a process
+    pub fn close_fd(&self, pid: u32, fd: u32) -> &Self {
+        fs::remove_file(self.root.join(format!("{}/fd/{}", pid, fd))).unwrap();
+        self
+    }
+
+    /// Remove a process, as if it exited
+    pub fn exit(&self, pid: u32) -> &Self {
+        fs::remove_dir_all(self.root.join(pid.to_string())).unwrap();
+        self
+    }
+
+    /// Make "self" a link to a process, as it is for the reader of a real /proc
+    pub fn self_pid(&self, pid: u32) -> &Self {
+        let link = self.root.join("self");
+        let _ = fs::remove_file(&link);
+        symlink(pid.to_string(), link).unwrap();
+        self
+    }
+
+    /// Place a process (or "self") in a network namespace
+    pub fn netns(&self, pid: &str, inode: u64) -> &Self {
+        let ns_dir = self.root.join(format!("{}/ns", pid));
+        fs::create_dir_all(&ns_dir).unwrap();
+        let _ = fs::remove_file(ns_dir.join("net"));
+        symlink(format!("net:[{}]", inode), ns_dir.join("net")).unwrap();
+        self
+    }
+}
+
+impl Drop for FakeProc {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Format a /proc/net/{tcp,udp} row
+///
+/// Addresses are given as kernel hex (e.g. "0100007F" for 127.0.0.1).
+pub fn inet_row(local: &str, port: u16, state: u8, uid: u32, inode: u64) -> String {
+    format!(
+        "   0: {}:{:04X} 00000000:0000 {:02X} 00000000:00000000 00:00000000 00000000 {:>5}        0 {} 1 0000000000000000 100 0 0 10 0",
+        local, port, state, uid, inode
+    )
+}
@@ -0,0 +1,543 @@
+//! Scanner, resolver and process manager run against fake procfs trees
+
+mod common;
+
+use common::{inet_row, FakeProc};
+use portwatcher_lib::port_scanner::PortScanner;
+use portwatcher_lib::process_manager::ProcessManager;
+use portwatcher_lib::process_resolver::{ProcessResolver, STALE_FD_RESCANS};
+use portwatcher_lib::protection::ProtectionPolicy;
+use portwatcher_lib::types::{
+    AddressFamily, ContainerRuntime, KillResult, ProcessSignal, ProtectionReason, Protocol,
+    ScanError, ScanMode, TcpState,
+};
+use std::collections::HashSet;
+
+const TCP_LISTEN: u8 = 0x0A;
+const TCP_ESTABLISHED: u8 = 0x01;
+const UDP_UNCONNECTED: u8 = 0x07;
+
+#[test]
+fn test_scan_reads_fixture_tables() {
+    let fake = FakeProc::new("tables");
+    fake.inet_table(
+        "net/tcp",
+        &[
+            inet_row("0100007F", 8080, TCP_LISTEN, 1000, 1001),
+            inet_row("0100007F", 40000, TCP_ESTABLISHED, 1000, 1002),
+        ],
+    )
+    .inet_table(
+        "net/udp",
+        &[inet_row("00000000", 53, UDP_UNCONNECTED, 0, 1003)],
+    )
+    .inet_table(
+        "net/tcp6",
+        &[inet_row(
+            "00000000000000000000000000000000",
+            443,
+            TCP_LISTEN,
+            0,
+            1004,
+        )],
+    )
+    .netns("self", 4026531840);
+
+    let scanner = PortScanner::with_procfs(fake.procfs());
+    let mut entries = scanner.scan_all(ScanMode::Listening).unwrap();
+    entries.sort_by_key(|e| e.local_port);
+
+    let ports: Vec<(u16, Protocol, AddressFamily)> = entries
+        .iter()
+        .map(|e| (e.local_port, e.protocol, e.family))
+        .collect();
+    assert_eq!(
+        ports,
+        vec![
+            (53, Protocol::Udp, AddressFamily::Ipv4),
+            (443, Protocol::Tcp, AddressFamily::Ipv6),
+            (8080, Protocol::Tcp, AddressFamily::Ipv4),
+        ]
+    );
+    assert!(entries.iter().all(|e| e.netns == Some(4026531840)));
+
+    // The established connection only shows up when asked for
+    let all = scanner.scan_all(ScanMode::All).unwrap();
+    assert!(all
+        .iter()
+        .any(|e| e.local_port == 40000 && e.state == Some(TcpState::Established)));
+}
+
+#[test]
+fn test_scan_range_on_fixture() {
+    let fake = FakeProc::new("range");
+    fake.inet_table(
+        "net/tcp",
+        &[
+            inet_row("0100007F", 3000, TCP_LISTEN, 1000, 2001),
+            inet_row("0100007F", 5432, TCP_LISTEN, 1000, 2002),
+            inet_row("0100007F", 8080, TCP_LISTEN, 1000, 2003),
+        ],
+    );
+
+    let entries = PortScanner::with_procfs(fake.procfs())
+        .scan_range(3000, 6000, ScanMode::Listening)
+        .unwrap();
+
+    let mut ports: Vec<u16> = entries.iter().map(|e| e.local_port).collect();
+    ports.sort();
+    assert_eq!(ports, vec![3000, 5432]);
+}
+
+#[test]
+fn test_scan_missing_required_table() {
+    let fake = FakeProc::new("missing");
+    std::fs::remove_file(fake.root().join("net/tcp")).unwrap();
+
+    let result = PortScanner::with_procfs(fake.procfs()).scan_all(ScanMode::Listening);
+    match result {
+        Err(ScanError::IoError { path, .. }) => {
+            assert!(path.starts_with(&*fake.root().to_string_lossy()));
+            assert!(path.ends_with("net/tcp"));
+        }
+        other => panic!("expected IoError, got {:?}", other.map(|e| e.len())),
+    }
+}
+
+#[test]
+fn test_scan_connections_keeps_every_state_but_listen() {
+    let fake = FakeProc::new("connections");
+    fake.inet_table(
+        "net/tcp",
+        &[
+            inet_row("0100007F", 5432, TCP_LISTEN, 0, 6001),
+            inet_row("0100007F", 5432, TCP_ESTABLISHED, 0, 6002),
+            inet_row("0100007F", 5432, 0x03, 0, 6003), // SYN_RECV
+            inet_row("0100007F", 5432, 0x06, 0, 6004), // TIME_WAIT
+            inet_row("0100007F", 5432, 0x08, 0, 6005), // CLOSE_WAIT
+            inet_row("0100007F", 40000, TCP_ESTABLISHED, 0, 6006),
+        ],
+    )
+    .inet_table(
+        "net/udp",
+        &[inet_row("00000000", 5432, UDP_UNCONNECTED, 0, 6007)],
+    );
+
+    let mut connections = PortScanner::with_procfs(fake.procfs())
+        .scan_connections(5432)
+        .unwrap();
+    connections.sort_by_key(|e| e.inode);
+
+    let states: Vec<(u64, Option<TcpState>)> =
+        connections.iter().map(|e| (e.inode, e.state)).collect();
+    assert_eq!(
+        states,
+        vec![
+            (6002, Some(TcpState::Established)),
+            (6003, Some(TcpState::SynRecv)),
+            (6004, Some(TcpState::TimeWait)),
+            (6005, Some(TcpState::CloseWait)),
+        ]
+    );
+}
+
+#[test]
+fn test_scan_without_own_namespace_lists_sockets_once() {
+    // /proc/self/ns/net can't be read, so the host namespace isn't recognised
+    let row = inet_row("0100007F", 8080, TCP_LISTEN, 0, 3001);
+    let fake = FakeProc::new("no-own-netns");
+    fake.inet_table("net/tcp", std::slice::from_ref(&row))
+        .process(100, "host-app", 0, &[3001])
+        .netns("100", 1)
+        .inet_table("100/net/tcp", &[row])
+        .inet_table("100/net/udp", &[]);
+
+    let entries = PortScanner::with_procfs(fake.procfs())
+        .scan_all(ScanMode::Listening)
+        .unwrap();
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].local_port, 8080);
+}
+
+#[test]
+fn test_scan_other_namespace_from_fixture() {
+    let fake = FakeProc::new("netns");
+    fake.inet_table(
+        "net/tcp",
+        &[inet_row("0100007F", 8080, TCP_LISTEN, 0, 3001)],
+    )
+    .netns("self", 1)
+    .process(100, "host-app", 0, &[3001])
+    .netns("100", 1)
+    .process(200, "container-app", 0, &[3002])
+    .netns("200", 2)
+    .inet_table(
+        "200/net/tcp",
+        &[inet_row("00000000", 80, TCP_LISTEN, 0, 3002)],
+    )
+    .inet_table("200/net/udp", &[]);
+
+    let entries = PortScanner::with_procfs(fake.procfs())
+        .scan_all(ScanMode::Listening)
+        .unwrap();
+
+    let host = entries.iter().find(|e| e.local_port == 8080).unwrap();
+    let other = entries.iter().find(|e| e.local_port == 80).unwrap();
+    assert_eq!(host.netns, Some(1));
+    assert_eq!(other.netns, Some(2));
+    assert_eq!(entries.len(), 2);
+}
+
+#[test]
+fn test_scan_other_namespace_from_resolver_cache() {
+    let fake = FakeProc::new("netns-cache");
+    fake.inet_table(
+        "net/tcp",
+        &[inet_row("0100007F", 8080, TCP_LISTEN, 0, 3001)],
+    )
+    .netns("self", 1)
+    .process(100, "host-app", 0, &[3001])
+    .netns("100", 1)
+    .process(200, "container-app", 0, &[3002])
+    .netns("200", 2)
+    .inet_table(
+        "200/net/tcp",
+        &[inet_row("00000000", 80, TCP_LISTEN, 0, 3002)],
+    )
+    .inet_table("200/net/udp", &[]);
+
+    let mut resolver = ProcessResolver::with_procfs(fake.procfs());
+    let groups = resolver.pids_by_netns();
+    assert_eq!(groups.get(&1), Some(&vec![100]));
+    assert_eq!(groups.get(&2), Some(&vec![200]));
+
+    // A known process keeps its cached namespace; only a new one is read
+    fake.netns("100", 3)
+        .process(300, "sidecar", 0, &[])
+        .netns("300", 2);
+    resolver.refresh(&HashSet::new());
+    let groups = resolver.pids_by_netns();
+    assert_eq!(groups.get(&1), Some(&vec![100]));
+    assert_eq!(groups.get(&2), Some(&vec![200, 300]));
+    assert_eq!(groups.get(&3), None);
+
+    let entries = PortScanner::with_procfs(fake.procfs())
+        .with_namespaces(groups)
+        .scan_all(ScanMode::Listening)
+        .unwrap();
+    let other = entries.iter().find(|e| e.local_port == 80).unwrap();
+    assert_eq!(other.netns, Some(2));
+    assert_eq!(entries.len(), 2);
+}
+
+#[test]
+fn test_resolver_reads_fixture_processes() {
+    let fake = FakeProc::new("resolver");
+    fake.process(100, "nginx", 0, &[4001, 4002])
+        .process(200, "node", 1000, &[4003]);
+
+    let resolver = ProcessResolver::with_procfs(fake.procfs());
+
+    let nginx = resolver.resolve(4002).unwrap();
+    assert_eq!(nginx.pid, 100);
+    assert_eq!(nginx.name, "nginx");
+    assert_eq!(nginx.user, "root");
+    assert_eq!(nginx.container, None);
+
+    let node = resolver.resolve(4003).unwrap();
+    assert_eq!(node.pid, 200);
+    assert_eq!(node.name, "node");
+
+    assert!(resolver.resolve(9999).is_none());
+}
+
+#[test]
+fn test_resolver_reports_every_owner_of_shared_socket() {
+    // nginx master (root) forks two workers that inherit the listening socket
+    let fake = FakeProc::new("shared");
+    fake.child_process(1202, 1200, "nginx", 33, &[7001])
+        .child_process(1200, 1, "nginx", 0, &[7001])
+        .child_process(1201, 1200, "nginx", 33, &[7001, 7001]);
+
+    let resolver = ProcessResolver::with_procfs(fake.procfs());
+    let owners = resolver.resolve_all(7001);
+
+    let pids: Vec<u32> = owners.iter().map(|o| o.pid).collect();
+    assert_eq!(pids, vec![1200, 1201, 1202]);
+    assert_eq!(owners[0].user, "root");
+    assert_eq!(owners[1].ppid, 1200);
+
+    // The single-owner view picks the master
+    assert_eq!(resolver.resolve(7001).map(|o| o.pid), Some(1200));
+}
+
+#[test]
+fn test_resolver_walks_ancestry() {
+    // node started by `npm run dev` from an interactive shell
+    let fake = FakeProc::new("ancestry");
+    fake.process(1, "systemd", 0, &[])
+        .child_process(4000, 1, "bash", 1000, &[])
+        .child_process(4001, 4000, "npm", 1000, &[])
+        .child_process(4002, 4001, "node", 1000, &[7002]);
+
+    let resolver = ProcessResolver::with_procfs(fake.procfs());
+    let names: Vec<String> = resolver
+        .get_ancestry(4002)
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    assert_eq!(names, vec!["node", "npm", "bash", "systemd"]);
+
+    // A parent outside the fixture (another PID namespace) ends the chain
+    fake.child_process(4003, 9999, "node", 1000, &[]);
+    assert_eq!(resolver.get_ancestry(4003).len(), 1);
+    assert!(resolver.get_ancestry(9999).is_empty());
+}
+
+#[test]
+fn test_resolver_refresh_tracks_process_changes() {
+    let fake = FakeProc::new("refresh");
+    fake.process(100, "server", 1000, &[8001]);
+
+    let mut resolver = ProcessResolver::with_procfs(fake.procfs());
+    assert_eq!(resolver.resolve(8001).map(|o| o.pid), Some(100));
+
+    // New process, new fd on a known process, and a closed fd
+    fake.process(200, "worker", 1000, &[8002])
+        .socket_fd(100, 4, 8003)
+        .close_fd(100, 3);
+    resolver.refresh(&HashSet::from([8002, 8003]));
+    assert_eq!(resolver.resolve(8002).map(|o| o.pid), Some(200));
+    assert_eq!(resolver.resolve(8003).map(|o| o.pid), Some(100));
+    assert!(resolver.resolve(8001).is_none());
+
+    // Exited process
+    fake.exit(200);
+    resolver.refresh(&HashSet::new());
+    assert!(resolver.resolve(8002).is_none());
+
+    // PID reused by a new process: its fds are rescanned from scratch
+    fake.start_time(100, 1, "other", 999_999)
+        .socket_fd(100, 4, 8004);
+    resolver.refresh(&HashSet::new());
+    assert_eq!(resolver.resolve(8004).map(|o| o.pid), Some(100));
+    assert!(resolver.resolve(8003).is_none());
+}
+
+#[test]
+fn test_resolver_refresh_reads_only_new_fd_dirs() {
+    let fake = FakeProc::new("fd-scans");
+    for pid in 100..110 {
+        fake.process(pid, "server", 1000, &[10_000 + pid as u64]);
+    }
+    let mut resolver = ProcessResolver::with_procfs(fake.procfs());
+    assert_eq!(resolver.fd_scans(), 10);
+
+    // Known sockets of unchanged processes are checked without listing any fd directory
+    let mut wanted: HashSet<u64> = (100..110).map(|pid| 10_000 + pid as u64).collect();
+    resolver.refresh(&wanted);
+    assert_eq!(resolver.fd_scans(), 10);
+
+    // A new process is the only one read for its socket
+    fake.process(200, "worker", 1000, &[10_200]);
+    wanted.insert(10_200);
+    resolver.refresh(&wanted);
+    assert_eq!(resolver.fd_scans(), 11);
+    assert_eq!(resolver.resolve(10_200).map(|o| o.pid), Some(200));
+}
+
+#[test]
+fn test_resolver_refresh_catches_reused_fd_number() {
+    let fake = FakeProc::new("reused-fd");
+    fake.process(100, "server", 1000, &[9001])
+        .file_fd(100, 4, "/var/log/server.log");
+
+    let mut resolver = ProcessResolver::with_procfs(fake.procfs());
+
+    // fd 3 closed and reopened as a different socket between scans
+    fake.socket_fd(100, 3, 9002);
+    resolver.refresh(&HashSet::from([9002]));
+    assert_eq!(resolver.resolve(9002).map(|o| o.pid), Some(100));
+    assert!(resolver.resolve(9001).is_none());
+
+    // Same for an fd that wasn't a socket before
+    fake.socket_fd(100, 4, 9003);
+    resolver.refresh(&HashSet::from([9003]));
+    assert_eq!(resolver.resolve(9003).map(|o| o.pid), Some(100));
+}
+
+#[test]
+fn test_resolver_retries_unknown_inodes_in_bounded_batches() {
+    let fake = FakeProc::new("batches");
+    let count = STALE_FD_RESCANS as u32 + 8;
+    for pid in 100..100 + count {
+        fake.process(pid, "server", 1000, &[]);
+    }
+    let mut resolver = ProcessResolver::with_procfs(fake.procfs());
+    let initial = resolver.fd_scans();
+
+    // An inode no process shows (another user's) costs one batch per refresh
+    let hidden = HashSet::from([7001]);
+    resolver.refresh(&hidden);
+    assert_eq!(resolver.fd_scans(), initial + STALE_FD_RESCANS as u64);
+
+    // The next batch starts with the processes read longest ago
+    fake.socket_fd(100 + count - 1, 3, 7002);
+    resolver.refresh(&HashSet::from([7002]));
+    assert_eq!(resolver.resolve(7002).map(|o| o.pid), Some(100 + count - 1));
+}
+
+#[test]
+fn test_resolver_drops_owner_whose_socket_fd_was_reused() {
+    let fake = FakeProc::new("reused-socket-fd");
+    fake.process(100, "server", 1000, &[9101])
+        .child_process(200, 100, "worker", 1000, &[9101]);
+
+    let mut resolver = ProcessResolver::with_procfs(fake.procfs());
+    assert_eq!(resolver.resolve_all(9101).len(), 2);
+
+    // The server closes the socket and fd 3 is reused for a log file;
+    // the worker keeps the socket alive
+    fake.file_fd(100, 3, "/var/log/server.log");
+    resolver.refresh(&HashSet::from([9101]));
+    let owners: Vec<u32> = resolver.resolve_all(9101).iter().map(|o| o.pid).collect();
+    assert_eq!(owners, vec![200]);
+}
+
+#[test]
+fn test_resolver_detects_container_from_fixture_cgroup() {
+    let id = "a".repeat(64);
+    let fake = FakeProc::new("cgroup");
+    fake.process(300, "postgres", 999, &[5001]).file(
+        "300/cgroup",
+        &format!("0::/system.slice/docker-{}.scope\n", id),
+    );
+
+    let info = ProcessResolver::with_procfs(fake.procfs())
+        .resolve(5001)
+        .unwrap();
+    let container = info.container.unwrap();
+
+    assert_eq!(container.runtime, ContainerRuntime::Docker);
+    assert_eq!(container.id, id);
+}
+
+#[test]
+fn test_resolver_keeps_container_names_of_seen_containers_only() {
+    let id = "b".repeat(64);
+    let fake = FakeProc::new("container-names");
+    fake.process(300, "postgres", 999, &[5001]).file(
+        "300/cgroup",
+        &format!("0::/system.slice/docker-{}.scope\n", id),
+    );
+    let mut resolver = ProcessResolver::with_procfs(fake.procfs());
+    let name = |resolver: &ProcessResolver| resolver.resolve(5001).unwrap().container.unwrap().name;
+
+    // Nothing is looked up while resolving, and a failed lookup leaves no entry
+    assert_eq!(name(&resolver), None);
+    resolver.update_container_names(&HashSet::from([id.clone()]), []);
+    assert_eq!(resolver.container_name(&id), None);
+
+    resolver.update_container_names(
+        &HashSet::from([id.clone()]),
+        [(id.clone(), "db".to_string())],
+    );
+    assert_eq!(name(&resolver).as_deref(), Some("db"));
+
+    // A container missing from a scan is forgotten
+    resolver.update_container_names(&HashSet::new(), []);
+    assert_eq!(name(&resolver), None);
+}
+
+#[test]
+fn test_unix_sockets_from_fixture() {
+    let fake = FakeProc::new("unix");
+    fake.unix_table(&[
+        "0000000000000000: 00000002 00000000 00010000 0001 01 6001 /run/fixture.sock",
+        "0000000000000000: 00000003 00000000 00000000 0001 03 6002",
+    ]);
+
+    let scanner = PortScanner::with_procfs(fake.procfs());
+
+    let listening = scanner.scan_unix(ScanMode::Listening).unwrap();
+    assert_eq!(listening.len(), 1);
+    assert_eq!(listening[0].path.as_deref(), Some("/run/fixture.sock"));
+
+    let all = scanner.scan_unix(ScanMode::All).unwrap();
+    assert_eq!(all.len(), 2);
+}
+
+#[test]
+fn test_process_manager_uses_fixture_root() {
+    // Our own PID exists on the real system but not in the fixture, so nothing is signalled
+    let fake = FakeProc::new("manager");
+    let manager = ProcessManager::with_procfs(fake.procfs());
+    let pid = std::process::id();
+
+    assert!(matches!(
+        manager.kill(pid, None),
+        KillResult::ProcessNotFound { pid: p } if p == pid
+    ));
+
+    fake.process(pid, "fixture", users::get_current_uid(), &[]);
+    assert!(manager.is_owned_by_current_user(pid));
+
+    fake.process(1, "init", users::get_current_uid() + 1, &[]);
+    assert!(!manager.is_owned_by_current_user(1));
+}
+
+#[test]
+fn test_process_manager_refuses_other_users_processes() {
+    // Root may signal anything, and the fixture PID may be a real process
+    if users::get_current_uid() == 0 {
+        return;
+    }
+    let fake = FakeProc::new("not-owned");
+    fake.process(4242, "postgres", users::get_current_uid() + 1, &[]);
+    let manager = ProcessManager::with_procfs(fake.procfs());
+
+    assert!(matches!(
+        manager.send_signal(4242, ProcessSignal::Sighup, None),
+        KillResult::PermissionDenied { pid: 4242 }
+    ));
+    assert!(matches!(
+        manager.kill(4242, None),
+        KillResult::PermissionDenied { pid: 4242 }
+    ));
+}
+
+#[test]
+fn test_protection_policy_on_fixture() {
+    // PortWatcher (PID 4100 in this namespace) runs in the session led by gnome-session
+    let fake = FakeProc::new("protection");
+    fake.process(4000, "gnome-session", 1000, &[])
+        .child_process(4100, 4000, "portwatcher", 1000, &[])
+        .file(
+            "4100/stat",
+            "4100 (portwatcher) S 4000 4000 4000 0 -1 0 0 0 0 0 0 0 0 0 20 0 1 0 5100 0 0\n",
+        )
+        .self_pid(4100)
+        .process(4200, "Xwayland", 1000, &[])
+        .process(4300, "gnome-remote-de", 1000, &[])
+        .file("4300/cmdline", "/usr/libexec/gnome-remote-desktop-daemon\0")
+        .process(4400, "node", 1000, &[]);
+
+    let procfs = fake.procfs();
+    let policy = ProtectionPolicy::new().with_names(["gnome-remote-desktop-daemon".to_string()]);
+    let reasons: Vec<Option<ProtectionReason>> = [1, 4100, 4000, 4200, 4300, 4400]
+        .iter()
+        .map(|&pid| policy.check(&procfs, pid))
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            Some(ProtectionReason::Init),
+            Some(ProtectionReason::PortWatcher),
+            Some(ProtectionReason::SessionLeader),
+            Some(ProtectionReason::DisplayServer),
+            Some(ProtectionReason::Configured),
+            None,
+        ]
+    );
+}
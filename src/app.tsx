@@ -31,11 +31,20 @@ export function App() {
 
   // Wrap kill process to show toast notifications
   const handleKillProcess = useCallback(
-    async (pid: number, elevated: boolean): Promise<KillResult> => {
-      const result = await killProcess(pid, elevated);
+    async (
+      pid: number,
+      elevated: boolean,
+      startTime?: number,
+      port?: number
+    ): Promise<KillResult> => {
+      const result = await killProcess(pid, elevated, startTime, port);
       switch (result.status) {
         case 'Success':
-          showToast('success', `Process ${pid} terminated successfully`);
+          if (result.stage === 'Sigkill') {
+            showToast('success', `Process ${pid} ignored SIGTERM and was force-killed`);
+          } else {
+            showToast('success', `Process ${pid} terminated successfully`);
+          }
           break;
         case 'PermissionDenied':
         case 'ElevationRequired':
@@ -44,6 +53,18 @@ export function App() {
         case 'ProcessNotFound':
           showToast('info', `Process ${pid} not found (may have already exited)`);
           break;
+        case 'IdentityMismatch':
+          showToast('info', `Process ${pid} exited and its PID was reused; nothing was killed`);
+          break;
+        case 'Protected':
+          showToast('error', `Process ${pid} is protected and was not killed`);
+          break;
+        case 'AuthenticationCancelled':
+          showToast('info', 'Authentication cancelled; nothing was killed');
+          break;
+        case 'NotAuthorized':
+          showToast('error', 'Not authorized to stop processes of other users');
+          break;
         case 'Error':
           showToast('error', result.message || 'Failed to kill process');
           break;
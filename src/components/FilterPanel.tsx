@@ -59,6 +59,7 @@ export function FilterPanel({ filters, onChange, onClear }: FilterPanelProps) {
         options={[
           { value: 'all', label: 'All States' },
           { value: 'listen', label: 'Listening' },
+          { value: 'established', label: 'Established' },
         ]}
         size="sm"
         class="w-[120px]"
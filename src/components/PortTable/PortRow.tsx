@@ -4,7 +4,12 @@ import type { PortInfo, KillResult } from '../../store/types';
 
 interface PortRowProps {
   port: PortInfo;
-  onKill: (pid: number, elevated: boolean) => Promise<KillResult>;
+  onKill: (
+    pid: number,
+    elevated: boolean,
+    startTime?: number,
+    port?: number
+  ) => Promise<KillResult>;
   style?: { [key: string]: string | number };
 }
 
@@ -13,9 +18,10 @@ export function PortRow({ port, onKill, style }: PortRowProps) {
   const [needsElevation, setNeedsElevation] = useState(false);
 
   const handleKill = async (elevated: boolean = false) => {
+    if (port.pid === null) return;
     setIsKilling(true);
     try {
-      const result = await onKill(port.pid, elevated);
+      const result = await onKill(port.pid, elevated, port.identity?.start_time, port.port);
 
       if (result.status === 'ElevationRequired' || result.status === 'PermissionDenied') {
         setNeedsElevation(true);
@@ -27,6 +33,8 @@ export function PortRow({ port, onKill, style }: PortRowProps) {
     }
   };
 
+  const processLabel = port.process_name ?? `owned by ${port.user}, process hidden`;
+
   return (
     <div
       class="table-row flex items-center px-4 py-2.5 group"
@@ -36,16 +44,29 @@ export function PortRow({ port, onKill, style }: PortRowProps) {
       <div class="w-[180px] flex-shrink-0">
         <span
           class="block truncate text-sm font-medium text-gray-200 group-hover:text-white transition-colors"
-          title={port.process_name}
+          title={port.container ? `${processLabel} (${port.container.runtime} ${port.container.id})` : processLabel}
         >
-          {port.process_name}
+          {port.process_name ?? <span class="italic text-cyber-muted">{processLabel}</span>}
+          {port.container && (
+            <span class="ml-1.5 font-mono text-xs text-cyber-muted">
+              [{port.container.name ?? port.container.id.slice(0, 12)}]
+            </span>
+          )}
         </span>
       </div>
 
       {/* PID */}
       <div class="w-[80px] flex-shrink-0">
-        <span class="font-mono text-xs text-cyber-muted tabular-nums">
-          {port.pid}
+        <span
+          class="font-mono text-xs text-cyber-muted tabular-nums"
+          title={
+            port.owners.length > 1
+              ? port.owners.map((o) => `${o.pid} ${o.process_name}${o.master ? ' (master)' : ''}`).join('\n')
+              : undefined
+          }
+        >
+          {port.pid ?? '—'}
+          {port.owners.length > 1 && ` +${port.owners.length - 1}`}
         </span>
       </div>
 
@@ -54,6 +75,14 @@ export function PortRow({ port, onKill, style }: PortRowProps) {
         <span class="font-mono text-xs text-neon-cyan font-medium tabular-nums">
           {port.port}
         </span>
+        {port.backlog_stalled && (
+          <span
+            class="ml-1 text-xs text-neon-orange"
+            title={`Accept queue not draining: ${port.rx_queue} connection(s) waiting`}
+          >
+            !
+          </span>
+        )}
       </div>
 
       {/* Protocol Badge */}
@@ -89,7 +118,7 @@ export function PortRow({ port, onKill, style }: PortRowProps) {
 
       {/* Action */}
       <div class="w-[60px] flex-shrink-0 flex justify-end">
-        {needsElevation ? (
+        {port.pid === null ? null : needsElevation ? (
           <button
             onClick={() => handleKill(true)}
             disabled={isKilling}
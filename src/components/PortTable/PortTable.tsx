@@ -6,7 +6,12 @@ import type { PortInfo, SortColumn, SortDirection, KillResult } from '../../stor
 
 interface PortTableProps {
   ports: PortInfo[];
-  onKill: (pid: number, elevated: boolean) => Promise<KillResult>;
+  onKill: (
+    pid: number,
+    elevated: boolean,
+    startTime?: number,
+    port?: number
+  ) => Promise<KillResult>;
   sortColumn: SortColumn;
   sortDirection: SortDirection;
   onSort: (column: SortColumn) => void;
@@ -123,7 +128,7 @@ export function PortTable({
           >
             {visiblePorts.map((port) => (
               <PortRow
-                key={`${port.pid}-${port.port}-${port.protocol}`}
+                key={`${port.pid}-${port.port}-${port.protocol}-${port.family}-${port.address}`}
                 port={port}
                 onKill={onKill}
               />
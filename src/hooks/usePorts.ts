@@ -1,13 +1,15 @@
 import { getPorts, scanPortRange, killProcess, killProcessElevated, getPresets } from '../lib/tauri';
-import { ports, isLoading, presets, lastRefresh, filteredPorts } from '../store/signals';
+import { ports, isLoading, presets, lastRefresh, filteredPorts, filters } from '../store/signals';
 import type { KillResult } from '../store/types';
 
 export function usePorts() {
   const fetchPorts = async () => {
     isLoading.value = true;
     try {
+      // Live connections are only returned when explicitly requested
+      const mode = filters.value.state === 'established' ? 'All' : 'Listening';
       const [portList, presetList] = await Promise.all([
-        getPorts(),
+        getPorts(mode),
         presets.value.length === 0 ? getPresets() : Promise.resolve(presets.value),
       ]);
       ports.value = portList;
@@ -40,14 +42,19 @@ export function usePorts() {
     }
   };
 
-  const handleKillProcess = async (pid: number, elevated: boolean): Promise<KillResult> => {
+  const handleKillProcess = async (
+    pid: number,
+    elevated: boolean,
+    startTime?: number,
+    port?: number
+  ): Promise<KillResult> => {
     try {
       const result = elevated
-        ? await killProcessElevated(pid)
-        : await killProcess(pid);
+        ? await killProcessElevated(pid, startTime, undefined, port)
+        : await killProcess(pid, startTime, undefined, port);
 
-      // If successful, refresh ports
-      if (result.status === 'Success') {
+      // If successful, or the listing was stale, refresh ports
+      if (result.status === 'Success' || result.status === 'IdentityMismatch') {
         await fetchPorts();
       }
 
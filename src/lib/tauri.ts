@@ -1,32 +1,171 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { PortInfo, KillResult, PortPreset, TrayStats } from '../store/types';
+import type {
+  FreePortResult,
+  PortInfo,
+  KillHistoryEntry,
+  KillResult,
+  KillTarget,
+  PortPreset,
+  ProcessDetails,
+  ProcessInfo,
+  ProcessKillResult,
+  ProcessSignal,
+  RelaunchResult,
+  ScanMode,
+  TrayStats,
+  UnixSocketInfo,
+} from '../store/types';
 
 /**
- * Get all listening ports with their process information
+ * Get all ports with their process information (listening sockets by default)
  */
-export async function getPorts(): Promise<PortInfo[]> {
-  return invoke<PortInfo[]>('get_ports');
+export async function getPorts(mode?: ScanMode): Promise<PortInfo[]> {
+  return invoke<PortInfo[]>('get_ports', { mode });
 }
 
 /**
  * Scan a specific port range
  */
-export async function scanPortRange(start: number, end: number): Promise<PortInfo[]> {
-  return invoke<PortInfo[]>('scan_port_range', { start, end });
+export async function scanPortRange(
+  start: number,
+  end: number,
+  mode?: ScanMode
+): Promise<PortInfo[]> {
+  return invoke<PortInfo[]>('scan_port_range', { start, end, mode });
+}
+
+/**
+ * Get the TCP connections on a local port, in every state but LISTEN
+ */
+export async function getPortConnections(port: number): Promise<PortInfo[]> {
+  return invoke<PortInfo[]>('get_port_connections', { port });
+}
+
+/**
+ * Get Unix domain sockets (listening sockets by default)
+ */
+export async function getUnixSockets(mode?: ScanMode): Promise<UnixSocketInfo[]> {
+  return invoke<UnixSocketInfo[]>('get_unix_sockets', { mode });
+}
+
+/**
+ * Get detailed information about a process (command line, resources, terminal...)
+ */
+export async function getProcessDetails(pid: number): Promise<ProcessDetails> {
+  return invoke<ProcessDetails>('get_process_details', { pid });
+}
+
+/**
+ * Get a process and its ancestors, nearest first (e.g. node, npm, bash, systemd)
+ */
+export async function getProcessAncestry(pid: number): Promise<ProcessInfo[]> {
+  return invoke<ProcessInfo[]>('get_process_ancestry', { pid });
 }
 
 /**
  * Kill a process by PID
  */
-export async function killProcess(pid: number): Promise<KillResult> {
-  return invoke<KillResult>('kill_process', { pid });
+export async function killProcess(
+  pid: number,
+  startTime?: number,
+  graceMs?: number,
+  port?: number
+): Promise<KillResult> {
+  return invoke<KillResult>('kill_process', { pid, startTime, graceMs, port });
 }
 
 /**
  * Kill a process by PID using elevated privileges (pkexec)
  */
-export async function killProcessElevated(pid: number): Promise<KillResult> {
-  return invoke<KillResult>('kill_process_elevated', { pid });
+export async function killProcessElevated(
+  pid: number,
+  startTime?: number,
+  graceMs?: number,
+  port?: number
+): Promise<KillResult> {
+  return invoke<KillResult>('kill_process_elevated', { pid, startTime, graceMs, port });
+}
+
+/**
+ * Get the most recent kill and signal attempts, newest first
+ */
+export async function getKillHistory(limit?: number): Promise<KillHistoryEntry[]> {
+  return invoke<KillHistoryEntry[]>('get_kill_history', { limit });
+}
+
+/**
+ * Start a killed process again, with its old working directory and environment
+ */
+export async function relaunch(killId: number, waitMs?: number): Promise<RelaunchResult> {
+  return invoke<RelaunchResult>('relaunch', { killId, waitMs });
+}
+
+/**
+ * Send a signal (SIGINT, SIGHUP, SIGSTOP, SIGCONT...) to a process
+ */
+export async function sendSignal(
+  pid: number,
+  signal: ProcessSignal,
+  startTime?: number
+): Promise<KillResult> {
+  return invoke<KillResult>('send_signal', { pid, signal, startTime });
+}
+
+/**
+ * Send a signal to a process using elevated privileges (pkexec)
+ */
+export async function sendSignalElevated(
+  pid: number,
+  signal: ProcessSignal,
+  startTime?: number
+): Promise<KillResult> {
+  return invoke<KillResult>('send_signal_elevated', { pid, signal, startTime });
+}
+
+/**
+ * Stop every process holding a port, then re-scan to check it was released
+ */
+export async function freePort(
+  port: number,
+  protocol?: PortInfo['protocol'],
+  graceMs?: number,
+  escalate?: boolean
+): Promise<FreePortResult> {
+  return invoke<FreePortResult>('free_port', { port, protocol, graceMs, escalate });
+}
+
+/**
+ * Kill several processes at once, with a result for each
+ */
+export async function killProcesses(
+  targets: KillTarget[],
+  graceMs?: number
+): Promise<ProcessKillResult[]> {
+  return invoke<ProcessKillResult[]>('kill_processes', { targets, graceMs });
+}
+
+/**
+ * Kill several processes using elevated privileges, with a single pkexec prompt
+ */
+export async function killProcessesElevated(
+  targets: KillTarget[],
+  graceMs?: number
+): Promise<ProcessKillResult[]> {
+  return invoke<ProcessKillResult[]>('kill_processes_elevated', { targets, graceMs });
+}
+
+/**
+ * Kill a process and all of its descendants
+ */
+export async function killProcessTree(pid: number, graceMs?: number): Promise<ProcessKillResult[]> {
+  return invoke<ProcessKillResult[]>('kill_process_tree', { pid, graceMs });
+}
+
+/**
+ * Kill every process in a process's group
+ */
+export async function killProcessGroup(pid: number, graceMs?: number): Promise<ProcessKillResult[]> {
+  return invoke<ProcessKillResult[]>('kill_process_group', { pid, graceMs });
 }
 
 /**
@@ -25,9 +25,9 @@ function compareBy(column: SortColumn, a: PortInfo, b: PortInfo): number {
     case 'port':
       return a.port - b.port;
     case 'process':
-      return a.process_name.localeCompare(b.process_name);
+      return (a.process_name ?? '').localeCompare(b.process_name ?? '');
     case 'pid':
-      return a.pid - b.pid;
+      return (a.pid ?? -1) - (b.pid ?? -1);
     case 'protocol':
       return a.protocol.localeCompare(b.protocol);
     case 'address':
@@ -48,11 +48,11 @@ export const filteredPorts = computed(() => {
     const query = searchQuery.value.toLowerCase();
     result = result.filter(
       (p) =>
-        p.process_name.toLowerCase().includes(query) ||
+        (p.process_name ?? '').toLowerCase().includes(query) ||
         p.port.toString().includes(query) ||
         p.address.toLowerCase().includes(query) ||
         p.user.toLowerCase().includes(query) ||
-        p.pid.toString().includes(query)
+        (p.pid?.toString() ?? '').includes(query)
     );
   }
 
@@ -63,6 +63,13 @@ export const filteredPorts = computed(() => {
     result = result.filter((p) => p.protocol === protocol);
   }
 
+  // Apply state filter
+  if (f.state === 'listen') {
+    result = result.filter((p) => p.state === null || p.state === 'Listen');
+  } else if (f.state === 'established') {
+    result = result.filter((p) => p.state === 'Established');
+  }
+
   // Apply user filter (current user)
   if (f.user === 'current') {
     // Get current user from first port or use a signal
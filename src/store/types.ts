@@ -1,20 +1,186 @@
 // Port information returned from Rust backend
 export interface PortInfo {
-  pid: number;
-  process_name: string;
+  // null when the owning process can't be inspected (another user's, without root)
+  pid: number | null;
+  process_name: string | null;
+  identity: ProcessIdentity | null;
   port: number;
-  protocol: 'Tcp' | 'Udp';
+  protocol: 'Tcp' | 'Udp' | 'UdpLite' | 'Raw' | 'Icmp' | 'Sctp';
   address: string;
+  family: 'Ipv4' | 'Ipv6';
+  state: TcpState | null;
+  remote_address: string | null;
+  remote_port: number | null;
+  tx_queue: number;
+  rx_queue: number;
+  backlog_stalled: boolean;
+  netns: number | null;
+  container: ContainerInfo | null;
+  owners: SocketOwner[];
+  uid: number;
+  user: string;
+}
+
+// A specific process: PID plus start time (clock ticks after boot), which a reused PID won't share
+export interface ProcessIdentity {
+  pid: number;
+  start_time: number;
+}
+
+// A process holding a socket; pre-fork servers share one between master and workers
+export interface SocketOwner {
+  pid: number;
+  ppid: number;
+  process_name: string;
   user: string;
+  start_time: number | null;
+  master: boolean;
 }
 
+// Container the owning process runs in, detected from its cgroup
+export interface ContainerInfo {
+  runtime: 'Docker' | 'Podman' | 'Containerd' | 'CriO';
+  id: string;
+  name: string | null;
+}
+
+// TCP connection state (null for connectionless protocols)
+export type TcpState =
+  | 'Established'
+  | 'SynSent'
+  | 'SynRecv'
+  | 'FinWait1'
+  | 'FinWait2'
+  | 'TimeWait'
+  | 'Close'
+  | 'CloseWait'
+  | 'LastAck'
+  | 'Listen'
+  | 'Closing'
+  | 'NewSynRecv';
+
+// Unix domain socket information returned from Rust backend
+export interface UnixSocketInfo {
+  pid: number | null; // null when the owning process can't be inspected
+  process_name: string | null;
+  path: string | null;
+  abstract_name: string | null;
+  socket_type: 'Stream' | 'Dgram' | 'SeqPacket';
+  state: 'Unconnected' | 'Connecting' | 'Connected' | 'Disconnecting';
+  listening: boolean;
+  inode: number;
+  user: string | null; // socket file owner when the process is hidden
+}
+
+// Detailed information about a single process
+export interface ProcessDetails {
+  pid: number;
+  ppid: number;
+  name: string;
+  cmdline: string[];
+  exe: string | null;
+  cwd: string | null;
+  start_time: number | null; // seconds since the Unix epoch
+  threads: number;
+  rss_bytes: number;
+  cpu_time_ms: number;
+  fd_count: number | null;
+  tty: string | null;
+  user: string;
+}
+
+// A process in an ancestry chain (get_process_ancestry)
+export interface ProcessInfo {
+  pid: number;
+  ppid: number;
+  name: string;
+  user: string;
+  start_time: number | null;
+  container: ContainerInfo | null;
+}
+
+// Which sockets the backend should return
+export type ScanMode = 'Listening' | 'All';
+
+// Signal that ended a killed process (SIGKILL when it ignored SIGTERM)
+export type KillStage = 'Sigterm' | 'Sigkill';
+
+// Signals the backend accepts for send_signal
+export type ProcessSignal = 'Sigterm' | 'Sigint' | 'Sighup' | 'Sigkill' | 'Sigstop' | 'Sigcont';
+
+// Why a process is refused any signal
+export type ProtectionReason =
+  | 'Init'
+  | 'PortWatcher'
+  | 'SessionLeader'
+  | 'DisplayServer'
+  | 'SystemService'
+  | 'Configured';
+
 // Result of kill process operation
 export interface KillResult {
-  status: 'Success' | 'PermissionDenied' | 'ElevationRequired' | 'ProcessNotFound' | 'Error';
+  status:
+    | 'Success'
+    | 'Signalled'
+    | 'PermissionDenied'
+    | 'ElevationRequired'
+    | 'ProcessNotFound'
+    | 'IdentityMismatch'
+    | 'Protected'
+    | 'AuthenticationCancelled'
+    | 'NotAuthorized'
+    | 'Error';
+  stage?: KillStage;
+  signal?: ProcessSignal;
+  reason?: ProtectionReason;
   pid?: number;
   message?: string;
 }
 
+// One process of a batch kill; start_time refuses a PID reused since it was listed
+export interface KillTarget {
+  pid: number;
+  start_time?: number;
+  port?: number;
+}
+
+// One kill or signal attempt from the kill history (the environment stays in the backend)
+export interface KillHistoryEntry {
+  id: number;
+  timestamp: number; // ms since the Unix epoch
+  pid: number;
+  name: string | null;
+  cmdline: string[];
+  cwd: string | null;
+  port: number | null;
+  signal: ProcessSignal;
+  elevated: boolean;
+  outcome: KillResult;
+}
+
+// A killed process started again from the kill history
+export interface RelaunchResult {
+  kill_id: number;
+  pid: number;
+  port: number | null;
+  port_reacquired: boolean | null;
+}
+
+// Per-process outcome of a tree or process group kill
+export interface ProcessKillResult {
+  pid: number;
+  result: KillResult;
+}
+
+// Outcome of free_port: which processes were stopped and whether the port is free now
+export interface FreePortResult {
+  port: number;
+  protocol: PortInfo['protocol'] | null;
+  stopped: ProcessKillResult[];
+  remaining: PortInfo[];
+  port_free: boolean;
+}
+
 // Port range for scanning
 export interface PortRange {
   start: number;
@@ -35,6 +201,8 @@ export interface TrayStats {
   total_ports: number;
   tcp_count: number;
   udp_count: number;
+  other_count: number; // UDP-Lite and SCTP
+  raw_count: number; // raw and ICMP sockets, which have no port
 }
 
 // Filter state